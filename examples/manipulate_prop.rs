@@ -5,10 +5,7 @@ use std::f32::consts::FRAC_PI_2;
 
 use avian3d::prelude::*;
 use avian_interpolation3d::prelude::*;
-use avian_pickup::{
-    prelude::*,
-    prop::{PreferredPickupDistanceOverride, PreferredPickupRotation},
-};
+use avian_pickup::prelude::*;
 use bevy::{
     app::RunFixedMainLoop,
     color::palettes::tailwind,
@@ -37,11 +34,15 @@ fn main() {
         // to the last variable timestep schedule before the fixed timestep systems run.
         .add_systems(
             RunFixedMainLoop,
-            (accumulate_input, handle_pickup_input, rotate_camera)
+            (
+                accumulate_input,
+                handle_pickup_input,
+                manipulate_prop,
+                rotate_camera,
+            )
                 .chain()
                 .in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
         )
-        .add_systems(FixedUpdate, move_prop)
         .run();
 }
 
@@ -66,6 +67,10 @@ fn setup(
             // Increase the maximum distance a bit to show off the
             // prop changing its distance on scroll.
             interaction_distance: 15.0,
+            hold: AvianPickupActorHoldConfig {
+                manipulation_distance_range: 0.5..=15.0,
+                ..default()
+            },
             ..default()
         },
         InputAccumulation::default(),
@@ -104,8 +109,6 @@ fn setup(
         RigidBody::Dynamic,
         Mass(5.0),
         Collider::from(box_shape),
-        PreferredPickupDistanceOverride::default(),
-        PreferredPickupRotation::default(),
     ));
 }
 
@@ -194,39 +197,29 @@ struct InputAccumulation {
     shift: bool,
 }
 
-fn move_prop(
-    time: Res<Time>,
-    mut actors: Query<(&mut InputAccumulation, &Transform, &AvianPickupActorState)>,
-    mut props: Query<(
-        &mut PreferredPickupDistanceOverride,
-        &mut PreferredPickupRotation,
-    )>,
+/// Rotate the prop while shift is pressed and move it with the scroll wheel.
+fn manipulate_prop(
+    mut manipulation_writer: EventWriter<AvianPickupManipulation>,
+    mut actors: Query<(Entity, &mut InputAccumulation), With<AvianPickupActor>>,
 ) {
-    let dt = time.delta_secs();
-    for (mut input, transform, state) in &mut actors {
-        let AvianPickupActorState::Holding(prop) = state else {
-            continue;
-        };
-        let (mut distance, mut rotation) = props.get_mut(*prop).unwrap();
-        const SCROLL_VELOCITY: f32 = 5.0;
-        let delta = input.zoom as f32 * SCROLL_VELOCITY * dt;
+    for (actor, mut input) in &mut actors {
+        // The factor is just an arbitrary scroll sensitivity value.
+        const SCROLL_DISTANCE: f32 = 0.5;
+        let distance = input.zoom as f32 * SCROLL_DISTANCE;
         input.zoom = 0;
-
-        distance.0 += delta;
-        distance.0 = distance.0.clamp(0.5, 15.0);
-
-        if !input.shift {
+        let rotation = if input.shift {
+            std::mem::take(&mut input.rotation)
+        } else {
+            Vec2::ZERO
+        };
+        if distance == 0.0 && rotation == Vec2::ZERO {
             continue;
         }
-
-        // Make the yaw global
-        let y_rotation_global = Quat::from_rotation_y(input.rotation.x * dt);
-
-        // Make the pitch relative to the actor's orientation
-        let horizontal_axis = transform.right().into();
-        let vertical_rotation = Quat::from_axis_angle(horizontal_axis, input.rotation.y * dt);
-        rotation.0 = vertical_rotation * y_rotation_global * rotation.0;
-
-        input.rotation = Vec2::ZERO;
+        manipulation_writer.send(AvianPickupManipulation {
+            actor,
+            yaw: rotation.x,
+            pitch: rotation.y,
+            distance,
+        });
     }
 }
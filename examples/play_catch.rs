@@ -271,15 +271,20 @@ fn tick_timer(time: Res<Time>, mut npcs: Query<&mut Npc>) {
 
 fn make_npc_catch(
     mut npcs: Query<(Entity, &Npc)>,
-    mut avian_pickup_input_writer: EventWriter<AvianPickupInput>,
+    props: Query<Entity, With<Prop>>,
+    mut avian_pickup_pull_target_writer: EventWriter<AvianPickupPullTarget>,
 ) {
+    let Ok(prop) = props.get_single() else {
+        return;
+    };
     for (entity, npc) in &mut npcs {
         if !matches!(npc.state, NpcState::Catching) {
             continue;
         }
-        avian_pickup_input_writer.send(AvianPickupInput {
-            action: AvianPickupAction::Pull,
+        // The NPC knows which prop to catch, so there's no need to search for it.
+        avian_pickup_pull_target_writer.send(AvianPickupPullTarget {
             actor: entity,
+            prop,
         });
     }
 }
@@ -306,16 +311,21 @@ fn on_npc_hold(
 
 fn on_aim_timer(
     mut npcs: Query<(Entity, &mut Npc)>,
-    mut avian_pickup_input_writer: EventWriter<AvianPickupInput>,
+    players: Query<Entity, With<Player>>,
+    mut avian_pickup_throw_at_writer: EventWriter<AvianPickupThrowAt>,
 ) {
+    let Ok(player) = players.get_single() else {
+        return;
+    };
     for (entity, mut npc) in &mut npcs {
         if !matches!(npc.state, NpcState::Aiming(..)) || !npc.timer.finished() {
             continue;
         }
         npc.waiting();
-        avian_pickup_input_writer.send(AvianPickupInput {
-            action: AvianPickupAction::Throw,
+        // Throw the prop on an arc that lands right at the player.
+        avian_pickup_throw_at_writer.send(AvianPickupThrowAt {
             actor: entity,
+            target: ThrowTarget::Entity(player),
         });
     }
 }
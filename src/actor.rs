@@ -8,28 +8,43 @@ use bevy::{
     prelude::*,
 };
 
+pub use crate::interaction::{HoldError, PlacementPreview, ShadowParams, ThrowCharge};
 use crate::{
-    interaction::{HoldError, ShadowParams},
-    prelude::Cooldown,
+    interaction::{HoldManipulation, LastHold, PendingGrabPoint},
+    prelude::{AvianPickupSlots, Cooldown, HeldProp, PropDropReason, PropDropped},
+    prop::NonPickupMass,
+    slot::SlotOwner,
+    verb::{Dropping, Holding, Pulling, Throwing},
 };
 
 pub(super) mod prelude {
     pub use super::{
-        AvianPickupActor, AvianPickupActorHoldConfig, AvianPickupActorPullConfig,
-        AvianPickupActorState, AvianPickupActorThrowConfig,
+        AlignmentSpace, AvianPickupActor, AvianPickupActorCooldownConfig,
+        AvianPickupActorHoldConfig, AvianPickupActorPullConfig, AvianPickupActorState,
+        AvianPickupActorThrowConfig, AvianPickupActorVelocity, HoldAlignment, HoldError, HoldMode,
+        HoldPlacement, NonDynamicPropPolicy, PlacementPreview, PropContestPolicy, ShadowParams,
+        SpinAxis, ThrowArc, ThrowCharge, ThrowChargeCurve,
     };
 }
 
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<(AvianPickupActor, AvianPickupActorState)>();
+    app.register_type::<(
+        AvianPickupActor,
+        AvianPickupActorState,
+        AvianPickupActorVelocity,
+    )>();
 }
 
 /// Tag component for an actor that is able to pick up object.
 /// For a first-person game, add this to the camera entity that is under the
-/// player control.
+/// player control. For a third-person game, you can add it to the character
+/// and set [`AvianPickupActor::aim_source`] to the camera.
 ///
 /// Requires the entity to also hold [`TransformBundle`].
 ///
+/// Removing this component or despawning the actor drops any held prop with
+/// [`PropDropReason::Lost`].
+///
 /// # Example
 /// ```
 /// # use avian_pickup::prelude::*;
@@ -53,7 +68,9 @@ pub(super) fn plugin(app: &mut App) {
 pub struct AvianPickupActor {
     /// The spatial query filter to use when looking for objects to pick up.\
     /// Note that no matter what this filter says, only entities with a
-    /// [`RigidBody::Dynamic`] will be considered in the first place.\
+    /// [`RigidBody::Dynamic`] will be considered in the first place,
+    /// unless configured otherwise by
+    /// [`AvianPickupActorPullConfig::non_dynamic_props`].\
     ///
     /// Default: Include all entities
     pub prop_filter: SpatialQueryFilter,
@@ -80,12 +97,38 @@ pub struct AvianPickupActor {
     ///
     /// Corresponds to Source's [`physcannon_cone`](https://developer.valvesoftware.com/wiki/Weapon_physcannon#physcannon_cone).
     pub interaction_cone: f32,
+    /// The entity whose transform is used to look for props and to determine
+    /// the direction in which props are held and thrown.
+    /// Useful for third-person games, where the camera aims
+    /// but the prop should be held by the character.
+    /// If `None` or if the entity has no transform, the actor's own transform
+    /// is used.\
+    /// Default: `None`
+    pub aim_source: Option<Entity>,
+    /// The entity whose position is used as the origin when holding props,
+    /// i.e. held props will float in front of this entity in the direction
+    /// of [`aim_source`](Self::aim_source).
+    /// If `None` or if the entity has no transform, the actor's own transform
+    /// is used.\
+    /// Default: `None`
+    pub hold_anchor: Option<Entity>,
+    /// The fraction of the actor's own linear velocity that is added to
+    /// thrown and dropped props, so that e.g. throwing while sprinting
+    /// throws further. 0.0 means that props are thrown and dropped as if the
+    /// actor was standing still, 1.0 means that they keep all of the actor's
+    /// momentum.
+    /// See [`AvianPickupActorVelocity`] for how the actor's velocity is
+    /// determined.\
+    /// Default: 0.0
+    pub inherited_velocity: Scalar,
     /// Configuration that is only used when pulling props to the actor.
     pub pull: AvianPickupActorPullConfig,
     /// Configuration that is only used while holding props.
     pub hold: AvianPickupActorHoldConfig,
     /// Configuration that is only used when throwing props.
     pub throw: AvianPickupActorThrowConfig,
+    /// Configuration for how long actions are blocked after another action.
+    pub cooldown: AvianPickupActorCooldownConfig,
 }
 
 /// Configuration that is only used when pulling props to the actor.
@@ -99,7 +142,8 @@ pub struct AvianPickupActor {
 )]
 pub struct AvianPickupActorPullConfig {
     /// How much impulse to be used when pulling objects to the player.
-    /// This is applied every 0.1 seconds.\
+    /// This is applied every
+    /// [`AvianPickupActorCooldownConfig::pull_interval`] seconds.\
     /// Default: 100.0 Ns
     ///
     /// Corresponds to Source's [`physcannon_pullforce`](https://developer.valvesoftware.com/wiki/Weapon_physcannon#physcannon_pullforce).
@@ -109,6 +153,19 @@ pub struct AvianPickupActorPullConfig {
     ///
     /// Corresponds to Source's [`physcannon_maxmass`](https://developer.valvesoftware.com/wiki/Weapon_physcannon#physcannon_maxmass).
     pub max_prop_mass: Scalar,
+    /// How to treat props that are not [`RigidBody::Dynamic`].
+    /// Can be overridden by adding a
+    /// [`NonDynamicPropPolicyOverride`](crate::prop::NonDynamicPropPolicyOverride)
+    /// to the prop.\
+    /// Default: [`NonDynamicPropPolicy::Ignore`]
+    pub non_dynamic_props: NonDynamicPropPolicy,
+    /// How strongly this actor pulls and holds props. Only relevant when
+    /// props are contested, see [`AvianPickupActorPullConfig::contest`].\
+    /// Default: 1.0
+    pub strength: Scalar,
+    /// Whether this actor can pull props away from other actors holding them.\
+    /// Default: [`PropContestPolicy::Never`]
+    pub contest: PropContestPolicy,
 }
 
 impl Default for AvianPickupActorPullConfig {
@@ -116,6 +173,86 @@ impl Default for AvianPickupActorPullConfig {
         Self {
             impulse: 100.0,
             max_prop_mass: 35.0,
+            non_dynamic_props: default(),
+            strength: 1.0,
+            contest: default(),
+        }
+    }
+}
+
+/// Whether an actor can take a prop away from another actor holding it.
+/// Used in [`AvianPickupActorPullConfig::contest`].
+///
+/// A contested prop can only be taken when it is in reach, as it is not
+/// affected by pull impulses while being held. The previous holder drops the
+/// prop with [`PropDropReason::Contested`](crate::prelude::PropDropReason::Contested).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum PropContestPolicy {
+    /// Props held by other actors are ignored.
+    #[default]
+    Never,
+    /// Props held by other actors can be taken if this actor's
+    /// [`AvianPickupActorPullConfig::strength`] is greater than the holder's.
+    StrongerActor,
+    /// Props held by other actors can always be taken.
+    Always,
+}
+
+impl PropContestPolicy {
+    /// Returns `true` if an actor with the given strength can take a prop
+    /// from a holder with the given strength under this policy.
+    pub fn allows(self, strength: Scalar, holder_strength: Scalar) -> bool {
+        match self {
+            Self::Never => false,
+            Self::StrongerActor => strength > holder_strength,
+            Self::Always => true,
+        }
+    }
+}
+
+/// How to treat props that are not [`RigidBody::Dynamic`].
+/// Used in [`AvianPickupActorPullConfig::non_dynamic_props`].
+///
+/// Note that props that are not dynamic are never pulled towards the actor,
+/// they can only be picked up when in reach. They cannot be thrown either, as
+/// they are not dynamic anymore once released, so throwing them drops them.
+/// Since such props might not have a [`Mass`] by default, make sure to add one.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum NonDynamicPropPolicy {
+    /// Only [`RigidBody::Dynamic`] props can be picked up.
+    #[default]
+    Ignore,
+    /// [`RigidBody::Kinematic`] props can be picked up as well.
+    /// They are moved by setting their velocity towards the hold target,
+    /// and come to a halt when they are no longer held.
+    /// [`RigidBody::Static`] props are still ignored.
+    MoveKinematic,
+    /// [`RigidBody::Kinematic`] and [`RigidBody::Static`] props can be picked
+    /// up. While held, they temporarily become [`RigidBody::Dynamic`].
+    /// Their original [`RigidBody`] is restored when they are no longer held.
+    MakeDynamic,
+}
+
+impl NonDynamicPropPolicy {
+    /// Returns `true` if a prop with the given [`RigidBody`] can be picked up
+    /// under this policy.
+    pub fn allows(self, rigid_body: RigidBody) -> bool {
+        match rigid_body {
+            RigidBody::Dynamic => true,
+            RigidBody::Kinematic => self != Self::Ignore,
+            RigidBody::Static => self == Self::MakeDynamic,
         }
     }
 }
@@ -177,10 +314,229 @@ pub struct AvianPickupActorHoldConfig {
     /// with other objects.
     /// The prop's original mass will be restored when the prop is no longer
     /// being held\
+    /// Not used in [`HoldMode::PdController`], which respects the real
+    /// mass.\
     /// Can be overridden by adding a
     /// [`PickupMassOverride`](crate::prop::PickupMassOverride) to the prop.\
     /// Default: 1 kg
     pub temporary_prop_mass: Scalar,
+    /// The accumulated distance in meters between the held prop and its target
+    /// position at which the prop will be forcefully dropped.
+    /// The current error can be read from the actor's
+    /// [`HoldError`] component.\
+    /// Default: 0.3048 m
+    ///
+    /// Corresponds to the 12 inches used by Source.
+    pub max_error: Scalar,
+    /// The distance in meters between the hold anchor and the held prop at
+    /// which the prop will be forcefully dropped with
+    /// [`PropDropReason::OutOfRange`], e.g. because the prop got stuck behind
+    /// a door while the actor kept walking.
+    /// `None` means that the prop is only dropped because of
+    /// [`AvianPickupActorHoldConfig::max_error`].\
+    /// Default: `None`
+    pub break_distance: Option<Scalar>,
+    /// How the held prop is moved towards its target.
+    /// Read when the prop is picked up, so changing this only affects
+    /// props picked up afterwards.\
+    /// Default: [`HoldMode::Velocity`]
+    pub mode: HoldMode,
+    /// The range in meters that the distance of the held prop can be changed
+    /// to with an [`AvianPickupManipulation`](crate::prelude::AvianPickupManipulation).\
+    /// Default: 0.5 m to 3.0 m
+    pub manipulation_distance_range: RangeInclusive<Scalar>,
+    /// The angle in radians that the held prop is rotated by per unit of
+    /// [`AvianPickupManipulation::yaw`](crate::prelude::AvianPickupManipulation::yaw)
+    /// and
+    /// [`AvianPickupManipulation::pitch`](crate::prelude::AvianPickupManipulation::pitch).\
+    /// Default: 1.0 rad
+    pub manipulation_rotation_speed: Scalar,
+    /// Whether to snap the held prop's rotation to the coordinate axes.
+    /// Useful for building or puzzle games.\
+    /// Default: `None`
+    ///
+    /// Corresponds to Source's `AlignAngles`.
+    pub alignment: Option<HoldAlignment>,
+    /// Whether to look for a spot to place the held prop on.
+    /// The result is available in the actor's [`PlacementPreview`] and can be
+    /// committed with
+    /// [`AvianPickupAction::Place`](crate::prelude::AvianPickupAction::Place).\
+    /// Default: `None`
+    pub placement: Option<HoldPlacement>,
+    /// Whether to hold props at the point where the actor's view hit them
+    /// instead of at their origin, e.g. to pick up a plank by its end.
+    /// Held props also rotate around that point.
+    /// Can be overridden by adding a
+    /// [`PickupGrabPoint`](crate::prop::PickupGrabPoint) to the prop.\
+    /// Default: `false`
+    pub grab_at_hit_point: bool,
+}
+
+/// How a held prop is moved towards its target.
+/// Used in [`AvianPickupActorHoldConfig::mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum HoldMode {
+    /// Directly set the velocity of the prop so that it reaches its target.
+    /// This is very stable, but ignores other forces acting on the prop and
+    /// fights against constraints such as hinges.
+    #[default]
+    Velocity,
+    /// Attach the prop to a kinematic anchor with a [`FixedJoint`] and move
+    /// the anchor towards the target instead.
+    /// This lets the prop behave physically correct when it is also
+    /// constrained by other joints, e.g. a door on a hinge.
+    Joint {
+        /// The compliance of the joint, i.e. the inverse of its stiffness.
+        /// Higher values result in a softer, more springy connection.
+        compliance: Scalar,
+        /// The damping applied to the relative linear velocity of the prop
+        /// and the anchor.
+        linear_damping: Scalar,
+        /// The damping applied to the relative angular velocity of the prop
+        /// and the anchor.
+        angular_damping: Scalar,
+    },
+    /// Drive the prop towards its target with a proportional-derivative
+    /// controller applying an [`ExternalForce`] and [`ExternalTorque`].
+    /// In contrast to the other modes, the prop keeps its real mass, so heavy
+    /// props lag behind and swing around. Note that gravity still acts on the
+    /// prop, so the stiffness needs to be high enough to carry it.
+    /// The force and torque are applied on top of any [`ExternalForce`] and
+    /// [`ExternalTorque`] already acting on the prop.
+    /// When the force or torque needed to reach the target exceeds the
+    /// configured maximum, only the maximum is applied. If that lasts for
+    /// longer than `max_overload_duration`, the prop is dropped.
+    PdController {
+        /// The force in N applied per meter of distance to the target.
+        linear_stiffness: Scalar,
+        /// The force in N applied against the prop's linear velocity per m/s.
+        linear_damping: Scalar,
+        /// The torque in Nm applied per radian of rotation to the target.
+        angular_stiffness: Scalar,
+        /// The torque in Nm applied against the prop's angular velocity per
+        /// rad/s.
+        angular_damping: Scalar,
+        /// The maximum force in N that can be applied before the prop is
+        /// dropped.
+        max_force: Scalar,
+        /// The maximum torque in Nm that can be applied before the prop is
+        /// dropped.
+        max_torque: Scalar,
+        /// The time in seconds that the force or torque can exceed their
+        /// maximum before the prop is dropped, e.g. to let a prop that was
+        /// just picked up from far away catch up with its target.
+        max_overload_duration: Scalar,
+    },
+}
+
+impl HoldMode {
+    /// A [`HoldMode::Joint`] with a slightly springy connection.
+    pub const JOINT: Self = Self::Joint {
+        compliance: 1e-4,
+        linear_damping: 10.0,
+        angular_damping: 10.0,
+    };
+
+    /// A [`HoldMode::PdController`] able to carry props of a few kg.
+    pub const PD_CONTROLLER: Self = Self::PdController {
+        linear_stiffness: 1000.0,
+        linear_damping: 60.0,
+        angular_stiffness: 50.0,
+        angular_damping: 5.0,
+        max_force: 2000.0,
+        max_torque: 200.0,
+        max_overload_duration: 0.5,
+    };
+}
+
+/// How to snap the rotation of a held prop.
+/// Used in [`AvianPickupActorHoldConfig::alignment`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct HoldAlignment {
+    /// The coordinate axes to align the prop's axes with.\
+    /// Default: [`AlignmentSpace::World`]
+    pub space: AlignmentSpace,
+    /// The maximum angle in radians between the prop's rotation and the
+    /// closest axis-aligned rotation at which the prop is snapped to it.\
+    /// Default: 30.0.to_radians()
+    pub threshold: Scalar,
+    /// If set, a prop that was rotated with an
+    /// [`AvianPickupManipulation`](crate::prelude::AvianPickupManipulation)
+    /// snaps its yaw, pitch and roll to multiples of this angle in radians
+    /// instead of to the coordinate axes.\
+    /// Default: `None`
+    pub increment: Option<Scalar>,
+}
+
+impl Default for HoldAlignment {
+    fn default() -> Self {
+        Self {
+            space: default(),
+            threshold: (30.0 as Scalar).to_radians(),
+            increment: None,
+        }
+    }
+}
+
+/// How to look for a spot to place a held prop on.
+/// Used in [`AvianPickupActorHoldConfig::placement`].
+///
+/// The prop's colliders are cast along the actor's view direction, using
+/// [`AvianPickupActor::obstacle_filter`] to find surfaces.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct HoldPlacement {
+    /// The maximum distance in meters from the actor at which a surface can be
+    /// found.\
+    /// Default: 5.0 m
+    pub max_distance: Scalar,
+    /// Whether to tilt the prop so that its up axis matches the surface
+    /// normal, e.g. to place a picture flush against a wall.\
+    /// Default: `true`
+    pub align_to_surface: bool,
+}
+
+impl Default for HoldPlacement {
+    fn default() -> Self {
+        Self {
+            max_distance: 5.0,
+            align_to_surface: true,
+        }
+    }
+}
+
+/// The coordinate axes a held prop is aligned with.
+/// Used in [`HoldAlignment::space`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AlignmentSpace {
+    /// Align with the world's axes, e.g. to keep crates upright.
+    #[default]
+    World,
+    /// Align with the actor's axes, e.g. to hold a crate face-on.
+    Actor,
 }
 
 impl Default for AvianPickupActorHoldConfig {
@@ -193,6 +549,14 @@ impl Default for AvianPickupActorHoldConfig {
             pitch_range: (-75.0_f32).to_radians()..=75.0_f32.to_radians(),
             preferred_distance: 1.25,
             temporary_prop_mass: 1.0,
+            max_error: 0.3048,
+            break_distance: None,
+            mode: default(),
+            manipulation_distance_range: 0.5..=3.0,
+            manipulation_rotation_speed: 1.0,
+            alignment: None,
+            placement: None,
+            grab_at_hit_point: false,
         }
     }
 }
@@ -228,6 +592,22 @@ pub struct AvianPickupActorThrowConfig {
     /// to the prop.\
     /// Default: 0.0 rad/s to 1.0 rad/s
     pub angular_speed_range: RangeInclusive<Scalar>,
+    /// The axis the thrown prop spins around.\
+    /// Default: [`SpinAxis::Random`]
+    pub spin_axis: SpinAxis,
+    /// Which arc to use when throwing at a target with
+    /// [`AvianPickupThrowAt`](crate::prelude::AvianPickupThrowAt).\
+    /// Default: [`ThrowArc::Low`]
+    pub arc: ThrowArc,
+    /// The time in seconds it takes to fully charge a throw started with
+    /// [`AvianPickupAction::BeginThrowCharge`](crate::prelude::AvianPickupAction::BeginThrowCharge).\
+    /// Default: 1.0 s
+    pub charge_duration: Scalar,
+    /// How the charge of a charged throw maps to its linear speed.
+    /// No charge throws the prop with `linear_speed_range.start()`, a full
+    /// charge with the speed it would have been thrown with normally.\
+    /// Default: [`ThrowChargeCurve::Linear`]
+    pub charge_curve: ThrowChargeCurve,
 }
 
 impl Default for AvianPickupActorThrowConfig {
@@ -236,6 +616,143 @@ impl Default for AvianPickupActorThrowConfig {
             cutoff_mass_for_slowdown: 20.0,
             linear_speed_range: 0.0..=5.0,
             angular_speed_range: 0.0..=1.0,
+            spin_axis: default(),
+            arc: default(),
+            charge_duration: 1.0,
+            charge_curve: default(),
+        }
+    }
+}
+
+/// How the charge of a charged throw maps to its linear speed.
+/// Used in [`AvianPickupActorThrowConfig::charge_curve`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ThrowChargeCurve {
+    /// The speed grows linearly with the charge.
+    #[default]
+    Linear,
+    /// The speed grows slowly at first and quickly towards a full charge.
+    EaseIn,
+    /// The speed grows quickly at first and slowly towards a full charge.
+    EaseOut,
+    /// The speed grows slowly at the start and the end of the charge.
+    Smooth,
+}
+
+impl ThrowChargeCurve {
+    /// Maps a charge fraction between 0.0 and 1.0 to a speed fraction between
+    /// 0.0 and 1.0.
+    pub fn sample(self, charge: Scalar) -> Scalar {
+        let t = charge.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::Smooth => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// The axis a thrown prop spins around.
+/// Used in [`AvianPickupActorThrowConfig::spin_axis`] and
+/// [`ThrownSpin`](crate::prop::ThrownSpin).
+///
+/// The randomness is taken from the actor's
+/// [`AvianPickupRng`](crate::AvianPickupRng) if it has one, otherwise from
+/// the [`RngSource`](crate::RngSource).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum SpinAxis {
+    /// A random axis is chosen for every throw.
+    #[default]
+    Random,
+    /// A fixed axis in the actor's local space, e.g. [`Vec3::X`] for
+    /// backspin. Combine this with an
+    /// [`AvianPickupActorThrowConfig::angular_speed_range`] containing a
+    /// single value to disable random spin entirely.
+    Actor(Vec3),
+    /// A fixed axis in the prop's local space, e.g. [`Vec3::Y`] to spin a
+    /// frisbee around its up axis.
+    Prop(Vec3),
+}
+
+/// Which of the two possible ballistic arcs to use when throwing at a target.
+/// Used in [`AvianPickupActorThrowConfig::arc`].
+///
+/// The prop is always thrown with the speed it would have been thrown with
+/// normally, so both arcs hit the same target. If the target is too far away
+/// to be hit at that speed, the prop is thrown as far as possible towards it.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default, Reflect)]
+#[reflect(Debug, Default, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ThrowArc {
+    /// The flat arc that reaches the target the fastest.
+    #[default]
+    Low,
+    /// The lob that goes high up before falling down onto the target.
+    High,
+}
+
+/// Configuration for how long actions are blocked after another action.
+/// The current state of the cooldowns can be read from the actor's
+/// [`Cooldown`](crate::prelude::Cooldown) component.
+/// Used in [`AvianPickupActor::cooldown`].
+/// Can be overridden by adding a
+/// [`CooldownOverride`](crate::prop::CooldownOverride) to the prop.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupActorCooldownConfig {
+    /// The time in seconds after throwing a prop until the actor can pull
+    /// again.\
+    /// Default: 0.5 s
+    pub pull_after_throw: f32,
+    /// The time in seconds after dropping a prop until the actor can pull
+    /// again.\
+    /// Default: 0.5 s
+    pub pull_after_drop: f32,
+    /// The time in seconds after picking up a prop until the actor can drop
+    /// it.\
+    /// Default: 0.4 s
+    ///
+    /// Source sneakily updates this in two places, once with
+    /// [0.5 s](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2316)
+    /// and once with
+    /// [0.4 s](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2438).
+    /// We use just 0.4 s, as that feels nicer.
+    pub drop_after_hold: f32,
+    /// The time in seconds between two pull impulses, as configured by
+    /// [`AvianPickupActorPullConfig::impulse`].\
+    /// Default: 0.1 s
+    pub pull_interval: f32,
+}
+
+impl Default for AvianPickupActorCooldownConfig {
+    fn default() -> Self {
+        Self {
+            pull_after_throw: 0.5,
+            pull_after_drop: 0.5,
+            drop_after_hold: 0.4,
+            pull_interval: 0.1,
         }
     }
 }
@@ -289,13 +806,74 @@ impl Default for AvianPickupActor {
             actor_filter: default(),
             interaction_distance: 1.5,
             interaction_cone: 0.92,
+            aim_source: None,
+            hold_anchor: None,
+            inherited_velocity: 0.0,
             pull: default(),
             hold: default(),
             throw: default(),
+            cooldown: default(),
         }
     }
 }
 
+/// The linear velocity in m/s of an [`AvianPickupActor`], used for
+/// [`AvianPickupActor::inherited_velocity`].
+/// Insert this on the actor or one of its ancestors and keep it updated if
+/// the actor is not moved by a rigid body, e.g. for kinematic character
+/// controllers.
+///
+/// Without this component, the [`LinearVelocity`] of the actor is used.
+/// If the actor has none, the closest ancestor with one is used instead,
+/// e.g. the player's rigid body when the actor is its camera, followed by the
+/// owner of the actor's [`AvianPickupSlots`](crate::prelude::AvianPickupSlots).
+#[derive(Debug, Clone, Copy, PartialEq, Component, Default, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupActorVelocity(pub Vec3);
+
+/// Finds the velocity of the body moving the actor, see
+/// [`AvianPickupActorVelocity`].
+pub(crate) fn actor_velocity(
+    actor: Entity,
+    linear_velocity: impl Fn(Entity) -> Option<Vec3>,
+    q_velocity_source: &Query<&AvianPickupActorVelocity>,
+    q_parent: &Query<&Parent>,
+    q_slot_owner: &Query<&SlotOwner>,
+    q_slots: &Query<&AvianPickupSlots>,
+) -> Vec3 {
+    let slot_owner =
+        AvianPickupSlots::find_owner(actor, q_slot_owner, q_slots).map(|(owner, _slots)| owner);
+    std::iter::once(actor)
+        .chain(q_parent.iter_ancestors(actor))
+        .chain(slot_owner)
+        .find_map(|entity| {
+            q_velocity_source
+                .get(entity)
+                .ok()
+                .map(|velocity| velocity.0)
+                .or_else(|| linear_velocity(entity))
+        })
+        .unwrap_or_default()
+}
+
+impl AvianPickupActor {
+    /// The entity to aim from, see [`AvianPickupActor::aim_source`].
+    pub(crate) fn aim_entity(&self, actor: Entity) -> Entity {
+        self.aim_source.unwrap_or(actor)
+    }
+
+    /// The entity to hold props relative to, see
+    /// [`AvianPickupActor::hold_anchor`].
+    pub(crate) fn hold_anchor_entity(&self, actor: Entity) -> Entity {
+        self.hold_anchor.unwrap_or(actor)
+    }
+}
+
 impl Component for AvianPickupActor {
     const STORAGE_TYPE: StorageType = StorageType::Table;
 
@@ -307,7 +885,66 @@ impl Component for AvianPickupActor {
                 Cooldown::default(),
                 HoldError::default(),
                 ShadowParams::default(),
+                HoldManipulation::default(),
             ));
         });
+        hooks.on_remove(|mut world, targeted_entity, _component_id| {
+            if let Some(prop) = world.get::<Holding>(targeted_entity).map(|h| h.0) {
+                // The prop is released right away, so report its current state.
+                let temporary_mass = world.get::<Mass>(prop).map(|m| m.0).unwrap_or_default();
+                let hold_duration = world
+                    .get::<HeldProp>(prop)
+                    .filter(|held| held.actor == targeted_entity)
+                    .map(|held| held.duration)
+                    .unwrap_or_default();
+                let event = PropDropped {
+                    prop,
+                    actor: targeted_entity,
+                    position: world.get::<Position>(prop).map(|p| p.0).unwrap_or_default(),
+                    linear_velocity: world
+                        .get::<LinearVelocity>(prop)
+                        .map(|v| v.0)
+                        .unwrap_or_default(),
+                    angular_velocity: world
+                        .get::<AngularVelocity>(prop)
+                        .map(|v| v.0)
+                        .unwrap_or_default(),
+                    mass: world
+                        .get::<NonPickupMass>(prop)
+                        .map(|m| m.0)
+                        .unwrap_or(temporary_mass),
+                    temporary_mass,
+                    hold_duration,
+                    forced: PropDropReason::Lost.is_forced(),
+                    reason: PropDropReason::Lost,
+                };
+                world.send_event(event);
+                world
+                    .commands()
+                    .trigger_targets(event, [prop, targeted_entity]);
+            }
+            // The actor might be getting despawned, so don't panic if it's gone.
+            // Removing `Holding` restores the prop's state.
+            world.commands().queue(move |world: &mut World| {
+                if let Ok(mut actor) = world.get_entity_mut(targeted_entity) {
+                    actor.remove::<(
+                        AvianPickupActorState,
+                        Cooldown,
+                        HoldError,
+                        ShadowParams,
+                        HoldManipulation,
+                        PlacementPreview,
+                        LastHold,
+                        Holding,
+                        Throwing,
+                        Dropping,
+                        Pulling,
+                        ThrowCharge,
+                        PendingGrabPoint,
+                        SlotOwner,
+                    )>();
+                }
+            });
+        });
     }
 }
@@ -0,0 +1,242 @@
+//! Module for directly controlling actors from code, bypassing
+//! [`AvianPickupInput`](crate::prelude::AvianPickupInput).
+
+use bevy::{
+    ecs::system::{EntityCommand, RunSystemOnce},
+    prelude::*,
+};
+
+use crate::{
+    interaction::pickup_prop,
+    prelude::PropDropReason,
+    verb::{Holding, SetVerb, ThrowAim, Verb},
+};
+
+pub(super) mod prelude {
+    pub use super::AvianPickupCommandsExt;
+}
+
+/// Extension trait for [`EntityCommands`] of an
+/// [`AvianPickupActor`](crate::prelude::AvianPickupActor).
+/// Useful for cutscenes, level scripts and tests.
+///
+/// In contrast to sending an
+/// [`AvianPickupInput`](crate::prelude::AvianPickupInput), these commands
+/// are not gated by the actor's [`Cooldown`](crate::prelude::Cooldown) and are
+/// applied together with the commands instead of waiting for the next
+/// [`PostUpdate`]. They still start the cooldowns as usual.
+/// The usual events are still sent once the physics have been updated.
+///
+/// # Example
+/// ```
+/// # use avian_pickup::prelude::*;
+/// # use bevy::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Key;
+///
+/// fn hand_over_key(
+///     mut commands: Commands,
+///     q_npc: Query<Entity, With<AvianPickupActor>>,
+///     q_key: Query<Entity, With<Key>>,
+/// ) {
+///     for (npc, key) in q_npc.iter().zip(q_key.iter()) {
+///         commands.entity(npc).pickup(key);
+///     }
+/// }
+/// ```
+pub trait AvianPickupCommandsExt {
+    /// Makes the actor hold the given prop, no matter how far away it is.
+    /// If the actor is already holding another prop, that one is dropped
+    /// first with [`PropDropReason::Scripted`].
+    ///
+    /// Props the actor could not pull, e.g. because they are too heavy or
+    /// held by another actor that cannot be contested, are ignored. See
+    /// [`AvianPickupActorPullConfig`](crate::prelude::AvianPickupActorPullConfig).
+    fn pickup(&mut self, prop: Entity) -> &mut Self;
+
+    /// Makes the actor drop the prop it is holding, if any, with
+    /// [`PropDropReason::Scripted`].
+    fn force_drop(&mut self) -> &mut Self;
+
+    /// Makes the actor throw the prop it is holding, if any, with the given
+    /// linear velocity in m/s. The angular velocity is determined as usual.
+    fn throw_with(&mut self, velocity: Vec3) -> &mut Self;
+}
+
+impl AvianPickupCommandsExt for EntityCommands<'_> {
+    fn pickup(&mut self, prop: Entity) -> &mut Self {
+        self.queue(Pickup(prop))
+    }
+
+    fn force_drop(&mut self) -> &mut Self {
+        self.queue(ForceDrop)
+    }
+
+    fn throw_with(&mut self, velocity: Vec3) -> &mut Self {
+        self.queue(ThrowWith(velocity))
+    }
+}
+
+struct Pickup(Entity);
+
+impl EntityCommand for Pickup {
+    fn apply(self, actor: Entity, world: &mut World) {
+        if let Err(err) = world.run_system_once_with((actor, self.0), pickup_prop) {
+            error!("Failed to pick up prop: {err}");
+        }
+    }
+}
+
+struct ForceDrop;
+
+impl EntityCommand for ForceDrop {
+    fn apply(self, actor: Entity, world: &mut World) {
+        let Some(prop) = held_prop(actor, world) else {
+            return;
+        };
+        SetVerb::new(Verb::Drop {
+            prop,
+            reason: PropDropReason::Scripted,
+        })
+        .apply(actor, world);
+    }
+}
+
+struct ThrowWith(Vec3);
+
+impl EntityCommand for ThrowWith {
+    fn apply(self, actor: Entity, world: &mut World) {
+        let Some(prop) = held_prop(actor, world) else {
+            return;
+        };
+        SetVerb::new(Verb::Throw {
+            prop,
+            aim: ThrowAim::Velocity(self.0),
+            charge: None,
+        })
+        .apply(actor, world);
+    }
+}
+
+fn held_prop(actor: Entity, world: &World) -> Option<Entity> {
+    world.get::<Holding>(actor).map(|holding| holding.0)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{prelude::*, verb::Dropping};
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Events<PropHoldLost>>();
+        world.init_resource::<Events<PropPullCancelled>>();
+        world
+    }
+
+    fn spawn_actor(world: &mut World) -> Entity {
+        let actor = world.spawn(AvianPickupActor::default()).id();
+        // Let the actor insert its required components.
+        world.flush();
+        actor
+    }
+
+    fn holding(world: &World, actor: Entity) -> Option<Entity> {
+        world.get::<Holding>(actor).map(|holding| holding.0)
+    }
+
+    #[test]
+    fn pickup_cancels_pull_and_starts_hold_cooldown() {
+        let mut world = world();
+        let actor = spawn_actor(&mut world);
+        let pulled = world.spawn((RigidBody::Dynamic, Mass(1.0))).id();
+        let prop = world.spawn((RigidBody::Dynamic, Mass(1.0))).id();
+        *world.get_mut::<AvianPickupActorState>(actor).unwrap() =
+            AvianPickupActorState::Pulling(pulled);
+
+        Pickup(prop).apply(actor, &mut world);
+
+        assert_eq!(holding(&world, actor), Some(prop));
+        let cancelled: Vec<_> = world
+            .resource::<Events<PropPullCancelled>>()
+            .iter_current_update_events()
+            .copied()
+            .collect();
+        assert_eq!(
+            cancelled,
+            [PropPullCancelled {
+                actor,
+                prop: pulled
+            }]
+        );
+        let cooldown = world.get::<Cooldown>(actor).unwrap();
+        assert!(cooldown.remaining(AvianPickupAction::Drop) > Duration::ZERO);
+    }
+
+    #[test]
+    fn pickup_ignores_props_that_cannot_be_pulled() {
+        let mut world = world();
+        let actor = spawn_actor(&mut world);
+        let max_prop_mass = AvianPickupActor::default().pull.max_prop_mass;
+        let heavy = world
+            .spawn((RigidBody::Dynamic, Mass(max_prop_mass + 1.0)))
+            .id();
+        let fixed = world.spawn((RigidBody::Static, Mass(1.0))).id();
+
+        Pickup(heavy).apply(actor, &mut world);
+        Pickup(fixed).apply(actor, &mut world);
+
+        assert_eq!(holding(&world, actor), None);
+    }
+
+    #[test]
+    fn pickup_respects_contest_policy() {
+        let mut world = world();
+        let holder = spawn_actor(&mut world);
+        let actor = spawn_actor(&mut world);
+        let prop = world
+            .spawn((
+                RigidBody::Dynamic,
+                Mass(1.0),
+                HeldProp {
+                    actor: holder,
+                    duration: default(),
+                },
+            ))
+            .id();
+        world.entity_mut(holder).insert(Holding(prop));
+
+        Pickup(prop).apply(actor, &mut world);
+        assert_eq!(holding(&world, actor), None);
+        assert_eq!(holding(&world, holder), Some(prop));
+
+        world
+            .get_mut::<AvianPickupActor>(actor)
+            .unwrap()
+            .pull
+            .contest = PropContestPolicy::Always;
+        Pickup(prop).apply(actor, &mut world);
+        assert_eq!(holding(&world, actor), Some(prop));
+        let dropping = world.get::<Dropping>(holder).unwrap();
+        assert_eq!(dropping.prop, prop);
+        assert_eq!(dropping.reason, PropDropReason::Contested);
+    }
+
+    #[test]
+    fn force_drop_is_scripted() {
+        let mut world = world();
+        let actor = spawn_actor(&mut world);
+        let prop = world.spawn((RigidBody::Dynamic, Mass(1.0))).id();
+        Pickup(prop).apply(actor, &mut world);
+
+        ForceDrop.apply(actor, &mut world);
+
+        assert_eq!(holding(&world, actor), None);
+        let dropping = world.get::<Dropping>(actor).unwrap();
+        assert_eq!(dropping.prop, prop);
+        assert_eq!(dropping.reason, PropDropReason::Scripted);
+    }
+}
@@ -1,70 +1,111 @@
+//! Module for the cooldowns that gate the actions of an
+//! [`AvianPickupActor`](crate::prelude::AvianPickupActor).
+
 use std::time::Duration;
 
 use avian3d::prelude::*;
-use bevy::{prelude::*, utils::hashbrown::HashMap};
+use bevy::prelude::*;
 
-use crate::{prelude::AvianPickupAction, AvianPickupSystem};
+use crate::{
+    prelude::{AvianPickupAction, AvianPickupActorCooldownConfig},
+    AvianPickupSystem,
+};
 
 pub(super) mod prelude {
-    pub(crate) use super::Cooldown;
+    pub use super::Cooldown;
 }
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(PhysicsSchedule, tick.in_set(AvianPickupSystem::TickTimers));
+    app.register_type::<Cooldown>()
+        .add_systems(PhysicsSchedule, tick.in_set(AvianPickupSystem::TickTimers));
 }
 
-/// Timings taken from [`CWeaponPhysCannon::SecondaryAttack`](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2284)
-#[derive(Debug, Clone, Component)]
-pub(crate) struct Cooldown(HashMap<AvianPickupAction, Timer>);
-
-impl Default for Cooldown {
-    fn default() -> Self {
-        let map = AvianPickupAction::iter()
-            .map(|action| (action, default()))
-            .collect();
-
-        Self(map)
-    }
+/// The cooldowns of an [`AvianPickupActor`](crate::prelude::AvianPickupActor).
+/// This component is automatically added to the entity holding the
+/// [`AvianPickupActor`](crate::prelude::AvianPickupActor), do not add or remove
+/// it.\
+/// Read this to e.g. show a recharge indicator in the HUD.
+/// The durations are configured by
+/// [`AvianPickupActor::cooldown`](crate::prelude::AvianPickupActor::cooldown).
+///
+/// Default timings taken from [`CWeaponPhysCannon::SecondaryAttack`](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2284)
+#[derive(Debug, Clone, Default, Component, Reflect)]
+#[reflect(Component, Debug, Default)]
+pub struct Cooldown {
+    throw: Timer,
+    drop: Timer,
+    pull: Timer,
 }
 
 impl Cooldown {
-    fn get(&self, action: &AvianPickupAction) -> &Timer {
-        // Safety: all actions are always present in the map as we initialize them in `default`.
-        self.0.get(action).unwrap()
+    /// Returns the timer gating the given action.
+    /// Variants of the same action share a timer, e.g.
+    /// [`AvianPickupAction::Place`] uses the one of
+    /// [`AvianPickupAction::Drop`],
+    /// [`AvianPickupPullTarget`](crate::prelude::AvianPickupPullTarget) the one
+    /// of [`AvianPickupAction::Pull`] and
+    /// [`AvianPickupThrowAt`](crate::prelude::AvianPickupThrowAt) the one of
+    /// [`AvianPickupAction::Throw`].
+    pub fn timer(&self, action: AvianPickupAction) -> &Timer {
+        match action {
+            AvianPickupAction::Throw
+            | AvianPickupAction::BeginThrowCharge
+            | AvianPickupAction::ReleaseThrowCharge => &self.throw,
+            AvianPickupAction::Drop | AvianPickupAction::Place => &self.drop,
+            AvianPickupAction::Pull => &self.pull,
+        }
+    }
+
+    fn timer_mut(&mut self, action: AvianPickupAction) -> &mut Timer {
+        match action {
+            AvianPickupAction::Throw
+            | AvianPickupAction::BeginThrowCharge
+            | AvianPickupAction::ReleaseThrowCharge => &mut self.throw,
+            AvianPickupAction::Drop | AvianPickupAction::Place => &mut self.drop,
+            AvianPickupAction::Pull => &mut self.pull,
+        }
     }
 
     fn set(&mut self, action: AvianPickupAction, seconds: f32) {
-        self.0
-            .insert(action, Timer::from_seconds(seconds, TimerMode::Once));
+        *self.timer_mut(action) = Timer::from_seconds(seconds, TimerMode::Once);
+    }
+
+    /// Returns `true` if the given action is not on cooldown.
+    pub fn finished(&self, action: AvianPickupAction) -> bool {
+        self.timer(action).finished()
+    }
+
+    /// Returns the time left until the given action is no longer on cooldown.
+    pub fn remaining(&self, action: AvianPickupAction) -> Duration {
+        self.timer(action).remaining()
     }
 
-    pub(crate) fn finished(&self, action: AvianPickupAction) -> bool {
-        self.get(&action).finished()
+    /// Returns how far the cooldown of the given action has progressed,
+    /// between 0.0 (just started) and 1.0 (finished).
+    pub fn fraction(&self, action: AvianPickupAction) -> f32 {
+        self.timer(action).fraction()
     }
 
-    pub(crate) fn throw(&mut self) {
-        // Happens to be the same as `drop`, but that's a coincidence.
-        self.set(AvianPickupAction::Pull, 0.5);
+    /// Throwing gates pulling, not throwing, so that the thrown prop is not
+    /// immediately pulled back.
+    pub(crate) fn throw(&mut self, config: &AvianPickupActorCooldownConfig) {
+        self.set(AvianPickupAction::Pull, config.pull_after_throw);
     }
 
-    pub(crate) fn drop(&mut self) {
-        self.set(AvianPickupAction::Pull, 0.5);
+    pub(crate) fn drop(&mut self, config: &AvianPickupActorCooldownConfig) {
+        self.set(AvianPickupAction::Pull, config.pull_after_drop);
     }
 
-    pub(crate) fn hold(&mut self) {
-        // Sneakily updated in two places:
-        // - [+ 0.5](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2316)
-        // - [+ 0.4](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2438)
-        // Let's use just 0.4, that feels nicer.
-        self.set(AvianPickupAction::Drop, 0.4);
+    pub(crate) fn hold(&mut self, config: &AvianPickupActorCooldownConfig) {
+        self.set(AvianPickupAction::Drop, config.drop_after_hold);
     }
 
-    pub(crate) fn pull(&mut self) {
-        self.set(AvianPickupAction::Pull, 0.1);
+    pub(crate) fn pull(&mut self, config: &AvianPickupActorCooldownConfig) {
+        self.set(AvianPickupAction::Pull, config.pull_interval);
     }
 
     pub(crate) fn tick(&mut self, time: Duration) {
-        for timer in self.0.values_mut() {
+        for timer in [&mut self.throw, &mut self.drop, &mut self.pull] {
             timer.tick(time);
         }
     }
@@ -1,21 +1,39 @@
 //! Module for the types that represent input events for Avian Pickup.
 
+use avian3d::math::Scalar;
 use bevy::{prelude::*, utils::HashSet};
 
 use crate::{
-    interaction::{HoldError, ShadowParams},
-    prelude::{AvianPickupActor, AvianPickupActorState, Cooldown},
-    verb::{SetVerb, Verb},
+    interaction::{HoldError, HoldManipulation, PlacementPreview, ShadowParams},
+    prelude::{
+        AvianPickupActor, AvianPickupActorState, AvianPickupSlots, Cooldown, PropDropReason,
+        ThrowCharge,
+    },
+    verb::{SetVerb, ThrowAim, Verb},
 };
 
 pub(super) mod prelude {
-    pub use super::{AvianPickupAction, AvianPickupInput};
+    pub use super::{
+        AvianPickupAction, AvianPickupInput, AvianPickupManipulation, AvianPickupPullTarget,
+        AvianPickupThrowAt, ThrowTarget,
+    };
 }
 
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<AvianPickupInput>()
-        .add_event::<AvianPickupInput>()
-        .add_systems(PostUpdate, set_verbs_according_to_input);
+    app.register_type::<(
+        AvianPickupInput,
+        AvianPickupPullTarget,
+        AvianPickupThrowAt,
+        AvianPickupManipulation,
+    )>()
+    .add_event::<AvianPickupInput>()
+    .add_event::<AvianPickupPullTarget>()
+    .add_event::<AvianPickupThrowAt>()
+    .add_event::<AvianPickupManipulation>()
+    .add_systems(
+        PostUpdate,
+        (set_verbs_according_to_input, accumulate_manipulation),
+    );
 }
 
 /// Event for picking up and throwing objects.
@@ -29,6 +47,8 @@ pub(super) fn plugin(app: &mut App) {
 )]
 pub struct AvianPickupInput {
     /// The entity of the [`AvianPickupActor`] that the event is related to.
+    /// This can also be an entity with [`AvianPickupSlots`], in which case the
+    /// action is routed to one of its slots.
     pub actor: Entity,
     /// The kind of input that the event represents.
     pub action: AvianPickupAction,
@@ -45,40 +65,257 @@ pub struct AvianPickupInput {
 pub enum AvianPickupAction {
     /// The left mouse button was just pressed this update.
     Throw,
+    /// Start charging a throw, e.g. because the left mouse button was just
+    /// pressed this update. While charging, the actor has a [`ThrowCharge`].
+    /// Shares its cooldown with [`AvianPickupAction::Throw`].
+    BeginThrowCharge,
+    /// Throw the held prop with the charge accumulated since
+    /// [`AvianPickupAction::BeginThrowCharge`], e.g. because the left mouse
+    /// button was just released this update.
+    /// Does nothing if no throw is being charged.
+    /// Shares its cooldown with [`AvianPickupAction::Throw`].
+    ReleaseThrowCharge,
     /// The right mouse button was just pressed this update.
     Drop,
+    /// Place the held prop where its [`PlacementPreview`] shows it, e.g.
+    /// because the middle mouse button was just pressed this update.
+    /// Does nothing if
+    /// [`AvianPickupActorHoldConfig::placement`](crate::prelude::AvianPickupActorHoldConfig::placement)
+    /// is not set or the placement is not valid.
+    /// Shares its cooldown with [`AvianPickupAction::Drop`].
+    Place,
     /// The right mouse button was pressed.
     Pull,
 }
 
-impl AvianPickupAction {
-    pub(crate) fn iter() -> impl Iterator<Item = Self> {
-        [Self::Throw, Self::Drop, Self::Pull].iter().copied()
+/// Event for pulling a specific prop.
+/// Like sending an [`AvianPickupInput`] with [`AvianPickupAction::Pull`], but
+/// only considers the given prop instead of searching for one in front of the
+/// actor.
+/// The prop still needs to be in range, in line of sight and pass the
+/// same checks as any other prop.
+/// Useful for AI and UI-driven interactions, e.g. "pick up this item".
+/// Shares its cooldown with [`AvianPickupAction::Pull`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupPullTarget {
+    /// The entity of the [`AvianPickupActor`] that should pull the prop.
+    /// This can also be an entity with [`AvianPickupSlots`], in which case the
+    /// pull is routed to one of its slots.
+    pub actor: Entity,
+    /// The prop to pull.
+    pub prop: Entity,
+}
+
+/// Any of the input events, unified so they can be handled in one place.
+#[derive(Debug, Clone, Copy)]
+enum Input {
+    Action(AvianPickupAction),
+    PullTarget(Entity),
+    ThrowAt(ThrowTarget),
+}
+
+impl Input {
+    /// Whether the input is routed to the slot that would pull a prop.
+    fn is_pull(self) -> bool {
+        matches!(
+            self,
+            Self::Action(AvianPickupAction::Pull) | Self::PullTarget(_)
+        )
+    }
+
+    /// Whether the input is routed to the slot that is charging a throw.
+    fn is_release_throw_charge(self) -> bool {
+        matches!(self, Self::Action(AvianPickupAction::ReleaseThrowCharge))
+    }
+}
+
+/// Event for throwing the held prop at a target.
+/// Like sending an [`AvianPickupInput`] with [`AvianPickupAction::Throw`], but
+/// the prop is thrown on a ballistic arc so that it hits the given target.
+/// Useful for AI and aim assist.
+/// The arc is chosen by
+/// [`AvianPickupActorThrowConfig::arc`](crate::prelude::AvianPickupActorThrowConfig::arc).
+/// Shares its cooldown with [`AvianPickupAction::Throw`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupThrowAt {
+    /// The entity of the [`AvianPickupActor`] that should throw its prop.
+    /// This can also be an entity with [`AvianPickupSlots`], in which case the
+    /// throw is routed to one of its slots.
+    pub actor: Entity,
+    /// What to throw the prop at.
+    pub target: ThrowTarget,
+}
+
+/// What to aim at when throwing with [`AvianPickupThrowAt`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ThrowTarget {
+    /// A point in world space.
+    Point(Vec3),
+    /// An entity. If it has a [`LinearVelocity`](avian3d::prelude::LinearVelocity),
+    /// the throw leads the target to hit it where it will be.
+    Entity(Entity),
+}
+
+/// Event for rotating a held prop and moving it closer or further away,
+/// a bit like how the physics gun in Garry's Mod works.
+/// Send this to e.g. rotate the prop with the mouse while a key is pressed and
+/// move it with the scroll wheel.
+/// Does nothing if the actor is not holding a prop.
+///
+/// Multiple events are accumulated until the next physics update.
+/// Once manipulated, the prop's rotation and distance take precedence over its
+/// [`PreferredPickupRotation`](crate::prop::PreferredPickupRotation) and
+/// [`PreferredPickupDistanceOverride`](crate::prop::PreferredPickupDistanceOverride)
+/// for the rest of the hold.
+/// The changes are reset when the prop is no longer held.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupManipulation {
+    /// The entity of the [`AvianPickupActor`] holding the prop.
+    /// This can also be an entity with [`AvianPickupSlots`], in which case the
+    /// manipulation is routed to the slot that would throw or drop.
+    pub actor: Entity,
+    /// How much to rotate the prop around the world's up axis.
+    /// Multiplied by
+    /// [`AvianPickupActorHoldConfig::manipulation_rotation_speed`](crate::prelude::AvianPickupActorHoldConfig::manipulation_rotation_speed)
+    /// to get the angle in radians.
+    pub yaw: Scalar,
+    /// How much to rotate the prop around the actor's right axis.
+    /// Multiplied by
+    /// [`AvianPickupActorHoldConfig::manipulation_rotation_speed`](crate::prelude::AvianPickupActorHoldConfig::manipulation_rotation_speed)
+    /// to get the angle in radians.
+    pub pitch: Scalar,
+    /// How many meters to move the prop away from the actor.
+    /// Negative values move it closer.
+    /// The resulting distance is clamped to
+    /// [`AvianPickupActorHoldConfig::manipulation_distance_range`](crate::prelude::AvianPickupActorHoldConfig::manipulation_distance_range).
+    pub distance: Scalar,
+}
+
+fn accumulate_manipulation(
+    mut r_manipulation: EventReader<AvianPickupManipulation>,
+    mut q_actor: Query<(&AvianPickupActorState, &mut HoldManipulation)>,
+    q_slots: Query<&AvianPickupSlots>,
+) {
+    for event in r_manipulation.read() {
+        let actor = if let Ok(slots) = q_slots.get(event.actor) {
+            let state = |slot| q_actor.get(slot).ok().map(|(&state, _)| state);
+            let Some(slot) = slots.slot_for_held_prop(state) else {
+                continue;
+            };
+            slot
+        } else {
+            event.actor
+        };
+        let Ok((state, mut manipulation)) = q_actor.get_mut(actor) else {
+            error!(
+                "`AvianPickupManipulation` was sent for an entity without `AvianPickupActor`. Ignoring."
+            );
+            continue;
+        };
+        if !state.is_holding() {
+            continue;
+        }
+        manipulation.pending_yaw += event.yaw;
+        manipulation.pending_pitch += event.pitch;
+        manipulation.pending_distance += event.distance;
     }
 }
 
 fn set_verbs_according_to_input(
     mut r_input: EventReader<AvianPickupInput>,
+    mut r_pull_target: EventReader<AvianPickupPullTarget>,
+    mut r_throw_at: EventReader<AvianPickupThrowAt>,
     mut commands: Commands,
-    q_actor: Query<
-        (
-            Entity,
-            Option<&AvianPickupActorState>,
-            Option<&Cooldown>,
-            Has<GlobalTransform>,
-            Has<ShadowParams>,
-            Has<HoldError>,
-        ),
-        With<AvianPickupActor>,
-    >,
+    q_actor: Query<(
+        Entity,
+        Option<&AvianPickupActorState>,
+        Option<&Cooldown>,
+        Has<GlobalTransform>,
+        Has<ShadowParams>,
+        Has<HoldError>,
+        &AvianPickupActor,
+        Option<&ThrowCharge>,
+        Option<&PlacementPreview>,
+    )>,
+    q_slots: Query<&AvianPickupSlots>,
 ) {
     let mut unhandled_actors: HashSet<_> = q_actor.iter().map(|(entity, ..)| entity).collect();
-    'outer: for &event in r_input.read() {
-        let action = event.action;
-        let actor = event.actor;
+    let inputs = r_input
+        .read()
+        .map(|event| (event.actor, Input::Action(event.action)))
+        .chain(
+            r_pull_target
+                .read()
+                .map(|event| (event.actor, Input::PullTarget(event.prop))),
+        )
+        .chain(
+            r_throw_at
+                .read()
+                .map(|event| (event.actor, Input::ThrowAt(event.target))),
+        );
+    'outer: for (actor, input) in inputs {
+        let actor = if let Ok(slots) = q_slots.get(actor) {
+            let state = |slot| {
+                q_actor
+                    .get(slot)
+                    .ok()
+                    .and_then(|(_, state, ..)| state.copied())
+            };
+            let slot = if input.is_pull() {
+                slots.slot_for_pull(state)
+            } else if input.is_release_throw_charge() {
+                slots.slot_for_throw_charge(|slot| {
+                    q_actor
+                        .get(slot)
+                        .is_ok_and(|(.., throw_charge, _)| throw_charge.is_some())
+                })
+            } else {
+                slots.slot_for_held_prop(state)
+            };
+            let Some(slot) = slot else {
+                // No slot is in a state where it could handle this action.
+                continue;
+            };
+            slot
+        } else {
+            actor
+        };
         unhandled_actors.remove(&actor);
-        let Ok((_entity, state, cooldown, has_global_transform, has_shadow, has_error)) =
-            q_actor.get(actor)
+        let Ok((
+            _entity,
+            state,
+            cooldown,
+            has_global_transform,
+            has_shadow,
+            has_error,
+            config,
+            throw_charge,
+            placement,
+        )) = q_actor.get(actor)
         else {
             error!(
                 "`AvianPickupEvent` was triggered on an entity without `AvianPickupActor`. Ignoring."
@@ -113,17 +350,59 @@ fn set_verbs_according_to_input(
             continue;
         };
 
-        let verb = match action {
-            AvianPickupAction::Throw
+        let verb = match input {
+            Input::Action(AvianPickupAction::Throw)
                 if cooldown.finished(AvianPickupAction::Throw)
                     && matches!(state, AvianPickupActorState::Holding(..)) =>
             {
                 let AvianPickupActorState::Holding(prop) = state else {
                     unreachable!()
                 };
-                Some(Verb::Throw(prop))
+                Some(Verb::Throw {
+                    prop,
+                    aim: ThrowAim::Forward,
+                    charge: None,
+                })
             }
-            AvianPickupAction::Drop
+            Input::Action(AvianPickupAction::BeginThrowCharge)
+                if cooldown.finished(AvianPickupAction::Throw)
+                    && matches!(state, AvianPickupActorState::Holding(..)) =>
+            {
+                if throw_charge.is_none() {
+                    commands
+                        .entity(actor)
+                        .insert(ThrowCharge::new(config.throw.charge_duration));
+                }
+                None
+            }
+            Input::Action(AvianPickupAction::ReleaseThrowCharge)
+                if throw_charge.is_some()
+                    && cooldown.finished(AvianPickupAction::Throw)
+                    && matches!(state, AvianPickupActorState::Holding(..)) =>
+            {
+                let AvianPickupActorState::Holding(prop) = state else {
+                    unreachable!()
+                };
+                Some(Verb::Throw {
+                    prop,
+                    aim: ThrowAim::Forward,
+                    charge: throw_charge.map(ThrowCharge::fraction),
+                })
+            }
+            Input::ThrowAt(target)
+                if cooldown.finished(AvianPickupAction::Throw)
+                    && matches!(state, AvianPickupActorState::Holding(..)) =>
+            {
+                let AvianPickupActorState::Holding(prop) = state else {
+                    unreachable!()
+                };
+                Some(Verb::Throw {
+                    prop,
+                    aim: ThrowAim::Target(target),
+                    charge: None,
+                })
+            }
+            Input::Action(AvianPickupAction::Drop)
                 if matches!(state, AvianPickupActorState::Holding(..))
                     && cooldown.finished(AvianPickupAction::Drop) =>
             {
@@ -132,16 +411,37 @@ fn set_verbs_according_to_input(
                 };
                 Some(Verb::Drop {
                     prop,
-                    forced: false,
+                    reason: PropDropReason::Player,
+                })
+            }
+            Input::Action(AvianPickupAction::Place)
+                if matches!(state, AvianPickupActorState::Holding(..))
+                    && placement.is_some_and(PlacementPreview::is_valid)
+                    && cooldown.finished(AvianPickupAction::Place) =>
+            {
+                let AvianPickupActorState::Holding(prop) = state else {
+                    unreachable!()
+                };
+                Some(Verb::Drop {
+                    prop,
+                    reason: PropDropReason::Placed,
                 })
             }
-            AvianPickupAction::Pull
+            Input::Action(AvianPickupAction::Pull)
+                if matches!(
+                    state,
+                    AvianPickupActorState::Idle | AvianPickupActorState::Pulling(..)
+                ) && cooldown.finished(AvianPickupAction::Pull) =>
+            {
+                Some(Verb::Pull(None))
+            }
+            Input::PullTarget(prop)
                 if matches!(
                     state,
                     AvianPickupActorState::Idle | AvianPickupActorState::Pulling(..)
                 ) && cooldown.finished(AvianPickupAction::Pull) =>
             {
-                Some(Verb::Pull)
+                Some(Verb::Pull(Some(prop)))
             }
             _ => None,
         };
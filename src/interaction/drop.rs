@@ -1,40 +1,121 @@
 use avian3d::math::{Scalar, TAU};
 
-use crate::{prelude::*, verb::Dropping};
+use crate::{
+    actor::actor_velocity, interaction::LastHold, prelude::*, slot::SlotOwner, verb::Dropping,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(PhysicsSchedule, drop.in_set(HandleVerbSystem::Drop).ambiguous_with(PhysicsStepSet::First));
 }
 
 /// DetachObject
-fn drop(
+pub(super) fn drop(
     mut commands: Commands,
-    mut q_actor: Query<(Entity, &mut AvianPickupActorState, &mut Cooldown, &Dropping)>,
-    mut q_prop: Query<(&mut LinearVelocity, &mut AngularVelocity)>,
+    mut q_actor: Query<(
+        Entity,
+        &AvianPickupActor,
+        &mut AvianPickupActorState,
+        &mut Cooldown,
+        &Dropping,
+        Option<&LastHold>,
+    )>,
+    mut q_velocity: Query<&mut LinearVelocity>,
+    mut q_prop: Query<(
+        &mut AngularVelocity,
+        &mut Position,
+        &mut Rotation,
+        &Mass,
+        Option<&NonPickupMass>,
+        Option<&CooldownOverride>,
+        &RigidBody,
+    )>,
+    q_velocity_source: Query<&AvianPickupActorVelocity>,
+    q_parent: Query<&Parent>,
+    q_slot_owner: Query<&SlotOwner>,
+    q_slots: Query<&AvianPickupSlots>,
     mut w_drop_event: EventWriter<PropDropped>,
 ) {
-    for (actor, mut state, mut cooldown, drop) in q_actor.iter_mut() {
+    for (actor, config, mut state, mut cooldown, drop, last_hold) in q_actor.iter_mut() {
         let prop = drop.prop;
-        *state = AvianPickupActorState::Idle;
-        cooldown.drop();
-        commands.entity(actor).remove::<Dropping>();
-        w_drop_event.send(PropDropped {
+        if !matches!(*state, AvianPickupActorState::Holding(held) if held != prop) {
+            *state = AvianPickupActorState::Idle;
+        }
+        commands.entity(actor).remove::<(Dropping, LastHold)>();
+        let last_hold = last_hold.filter(|last_hold| last_hold.prop == prop);
+        let hold_duration = last_hold.map(|l| l.duration).unwrap_or_default();
+        let inherited_velocity = actor_velocity(
             actor,
-            prop,
-            forced: drop.forced,
-        });
-        // Safety: the prop is a dynamic rigid body and thus is guaranteed to have a
-        // linvel and angvel.
-        let Ok((mut velocity, mut angvel)) = q_prop.get_mut(prop) else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
+            |entity| q_velocity.get(entity).ok().map(|v| v.0),
+            &q_velocity_source,
+            &q_parent,
+            &q_slot_owner,
+            &q_slots,
+        ) * config.inherited_velocity;
+        let (
+            Ok(mut velocity),
+            Ok((
+                mut angvel,
+                mut position,
+                mut rotation,
+                mass,
+                non_pickup_mass,
+                cooldown_override,
+                rigid_body,
+            )),
+        ) = (q_velocity.get_mut(prop), q_prop.get_mut(prop))
+        else {
+            // The prop was despawned, so there is nothing to drop.
+            let event = PropDropped {
+                actor,
+                prop,
+                position: Vec3::ZERO,
+                linear_velocity: Vec3::ZERO,
+                angular_velocity: Vec3::ZERO,
+                mass: 0.0,
+                temporary_mass: last_hold.map(|l| l.temporary_mass).unwrap_or_default(),
+                hold_duration,
+                forced: PropDropReason::Lost.is_forced(),
+                reason: PropDropReason::Lost,
+            };
+            w_drop_event.send(event);
+            commands.trigger_targets(event, [prop, actor]);
+            cooldown.drop(&config.cooldown);
             continue;
         };
+        cooldown.drop(cooldown_override.map(|c| &c.0).unwrap_or(&config.cooldown));
         // HL2 uses 190 inches per second, which is 4.826 meters per second.
         // let's round that to 5 m/s.
         const HL2_NORM_SPEED: Scalar = 5.0;
         const MAX_DROP_LINEAR_SPEED: Scalar = HL2_NORM_SPEED * 1.5;
         const MAX_DROP_ANGULAR_SPEED: Scalar = TAU * 2.0;
-        velocity.0 = velocity.clamp_length_max(MAX_DROP_LINEAR_SPEED);
+        velocity.0 = (velocity.0 + inherited_velocity).clamp_length_max(MAX_DROP_LINEAR_SPEED);
         angvel.0 = angvel.clamp_length_max(MAX_DROP_ANGULAR_SPEED);
+        if !rigid_body.is_dynamic() {
+            // Non-dynamic props would otherwise keep moving forever.
+            velocity.0 = Vec3::ZERO;
+            angvel.0 = Vec3::ZERO;
+        }
+        if let Some(placement) = drop.placement {
+            position.0 = placement.translation;
+            rotation.0 = placement.rotation;
+            velocity.0 = Vec3::ZERO;
+            angvel.0 = Vec3::ZERO;
+        }
+        // The prop's mass was already restored when it stopped being held.
+        let mass = non_pickup_mass.map(|m| m.0).unwrap_or(mass.0);
+        let event = PropDropped {
+            actor,
+            prop,
+            position: position.0,
+            linear_velocity: velocity.0,
+            angular_velocity: angvel.0,
+            mass,
+            temporary_mass: last_hold.map(|l| l.temporary_mass).unwrap_or(mass),
+            hold_duration,
+            forced: drop.reason.is_forced(),
+            reason: drop.reason,
+        };
+        w_drop_event.send(event);
+        commands.trigger_targets(event, [prop, actor]);
     }
 }
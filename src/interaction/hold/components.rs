@@ -1,33 +1,189 @@
+use std::{ops::RangeInclusive, time::Duration};
+
 use avian3d::math::{Scalar, TAU};
 use bevy::prelude::*;
 
+use crate::prelude::{AvianPickupActorHoldConfig, HoldMode};
+
 pub(super) fn plugin(_app: &mut App) {}
 
+/// The location that the prop held by an
+/// [`AvianPickupActor`](crate::prelude::AvianPickupActor) is trying to reach.
+/// This component is automatically added to the entity holding the
+/// [`AvianPickupActor`](crate::prelude::AvianPickupActor), do not add or remove
+/// it.\
+/// Read this to e.g. render a ghost of where the held prop is going.
+/// The values are only meaningful while the actor is
+/// [`AvianPickupActorState::Holding`](crate::prelude::AvianPickupActorState::Holding).
 #[derive(Debug, Copy, Clone, Component)]
-pub(crate) struct ShadowParams {
+pub struct ShadowParams {
     /// Global target position of the held prop
     pub(crate) target_position: Vec3,
     /// Global target rotation of the held prop
     pub(crate) target_rotation: Quat,
+    /// The held point in the prop's local space
+    pub(crate) grab_offset: Vec3,
     pub(crate) max_angular: Scalar,
     pub(crate) max_speed: Scalar,
 }
 
+impl ShadowParams {
+    /// The global position the held prop is trying to reach.
+    pub fn target_position(&self) -> Vec3 {
+        self.target_position
+    }
+
+    /// The global rotation the held prop is trying to reach.
+    pub fn target_rotation(&self) -> Quat {
+        self.target_rotation
+    }
+
+    /// The point in the held prop's local space that is held, i.e. that the
+    /// prop rotates around.
+    /// See [`PickupGrabPoint`](crate::prop::PickupGrabPoint) and
+    /// [`AvianPickupActorHoldConfig::grab_at_hit_point`].
+    pub fn grab_offset(&self) -> Vec3 {
+        self.grab_offset
+    }
+
+    /// The global transform the held prop is trying to reach.
+    pub fn target_transform(&self) -> Transform {
+        Transform::from_translation(self.target_position).with_rotation(self.target_rotation)
+    }
+}
+
 impl Default for ShadowParams {
     fn default() -> Self {
         Self {
             target_position: Vec3::ZERO,
             target_rotation: Quat::IDENTITY,
+            grab_offset: Vec3::ZERO,
             max_angular: TAU * 10.0,
             max_speed: 25.4,
         }
     }
 }
 
+/// The point in the prop's local space where an actor grabbed it, used when
+/// [`AvianPickupActorHoldConfig::grab_at_hit_point`] is set.
+/// Inserted when deciding to pick up a prop and consumed once it is held.
+#[derive(Debug, Copy, Clone, Component)]
+pub(crate) struct PendingGrabPoint {
+    pub(crate) prop: Entity,
+    pub(crate) offset: Vec3,
+}
+
+/// Where the held prop would end up when placing it with
+/// [`AvianPickupAction::Place`](crate::prelude::AvianPickupAction::Place).
+/// This component is automatically added to the entity holding the
+/// [`AvianPickupActor`](crate::prelude::AvianPickupActor) while it holds a prop
+/// and [`AvianPickupActorHoldConfig::placement`] is set, do not add or remove
+/// it. It is removed when no surface to place the prop on was found.\
+/// Read this to e.g. render a ghost of the placed prop.
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct PlacementPreview {
+    pub(crate) transform: Transform,
+    pub(crate) valid: bool,
+}
+
+impl PlacementPreview {
+    /// The global transform the prop would be placed with.
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Returns `true` if the prop would not overlap anything when placed.
+    /// Only valid placements can be committed.
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
+
+/// Changes to the held prop's rotation and distance requested through
+/// [`AvianPickupManipulation`](crate::prelude::AvianPickupManipulation).
+#[derive(Debug, Copy, Clone, Default, Component)]
+pub(crate) struct HoldManipulation {
+    /// Accumulated yaw input that has not been applied yet
+    pub(crate) pending_yaw: Scalar,
+    /// Accumulated pitch input that has not been applied yet
+    pub(crate) pending_pitch: Scalar,
+    /// Accumulated distance input that has not been applied yet
+    pub(crate) pending_distance: Scalar,
+    /// The manipulated rotation of the held prop in actor space
+    pub(crate) rotation: Option<Quat>,
+    /// The manipulated distance of the held prop
+    pub(crate) distance: Option<Scalar>,
+}
+
+impl HoldManipulation {
+    /// Applies the pending distance input to `distance`, clamped to `range`,
+    /// and returns the distance to hold the prop at.
+    pub(crate) fn apply_pending_distance(
+        &mut self,
+        distance: Scalar,
+        range: &RangeInclusive<Scalar>,
+    ) -> Scalar {
+        if self.pending_distance == 0.0 {
+            return distance;
+        }
+        let distance = (distance + self.pending_distance).clamp(*range.start(), *range.end());
+        self.pending_distance = 0.0;
+        self.distance = Some(distance);
+        distance
+    }
+}
+
+/// The [`HoldMode`] that was configured when the
+/// currently held prop was picked up.
+#[derive(Debug, Copy, Clone, Component)]
+pub(crate) struct ActiveHoldMode(pub(crate) HoldMode);
+
+/// What the [`HoldMode::PdController`](crate::prelude::HoldMode::PdController)
+/// of an actor currently applies to the held prop.
+/// Only present on the actor while holding a prop in that mode.
+#[derive(Debug, Copy, Clone, Default, Component)]
+pub(crate) struct PdControllerState {
+    /// The force added to the prop's [`ExternalForce`](avian3d::prelude::ExternalForce)
+    pub(crate) force: Vec3,
+    /// The torque added to the prop's [`ExternalTorque`](avian3d::prelude::ExternalTorque)
+    pub(crate) torque: Vec3,
+    /// Time in seconds that the force or torque have been exceeding their maximum
+    pub(crate) overload_time: Scalar,
+}
+
+/// Information about the last hold of an actor that is no longer available
+/// on the prop once it is released, so that it can be reported when throwing
+/// or dropping the prop.
+#[derive(Debug, Copy, Clone, Component)]
+pub(crate) struct LastHold {
+    /// The prop that was held
+    pub(crate) prop: Entity,
+    /// How long the prop was held for
+    pub(crate) duration: Duration,
+    /// The mass the prop had while being held
+    pub(crate) temporary_mass: Scalar,
+}
+
+/// The kinematic anchor and joint that hold a prop in
+/// [`HoldMode::Joint`](crate::prelude::HoldMode::Joint).
+/// Only present on the actor while holding a prop in that mode.
+#[derive(Debug, Copy, Clone, Component)]
+pub(crate) struct HoldJoint {
+    /// The kinematic rigid body that is moved towards the target.
+    pub(crate) anchor: Entity,
+    /// The joint connecting the anchor and the prop.
+    pub(crate) joint: Entity,
+}
+
 /// Cache for accumulating errors when holding an object.
-/// When this reaches a critical value, the object will be dropped.
+/// When this reaches
+/// [`AvianPickupActorHoldConfig::max_error`], the object will be dropped.\
+/// This component is automatically added to the entity holding the
+/// [`AvianPickupActor`](crate::prelude::AvianPickupActor), do not add or remove
+/// it.\
+/// Read this to e.g. warn the player before a forced drop happens.
 #[derive(Debug, Copy, Clone, Component)]
-pub(crate) struct HoldError {
+pub struct HoldError {
     /// Time until error starts accumulating
     pub(crate) error_time: f32,
     /// The distance between the object and the target position
@@ -35,6 +191,22 @@ pub(crate) struct HoldError {
 }
 
 impl HoldError {
+    /// The accumulated distance in meters between the held prop and its
+    /// target position.
+    pub fn error(&self) -> f32 {
+        self.error
+    }
+
+    /// The accumulated error relative to
+    /// [`AvianPickupActorHoldConfig::max_error`].
+    /// The prop will be dropped once this exceeds 1.0.
+    pub fn error_fraction(&self, config: &AvianPickupActorHoldConfig) -> f32 {
+        if config.max_error <= 0.0 {
+            return 1.0;
+        }
+        self.error / config.max_error
+    }
+
     pub(crate) fn reset(&mut self) {
         *self = Self::default();
     }
@@ -49,3 +221,25 @@ impl Default for HoldError {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn manipulated_distance_is_clamped() {
+        let range = 0.5..=3.0;
+        let mut manipulation = HoldManipulation::default();
+        assert_eq!(manipulation.apply_pending_distance(1.0, &range), 1.0);
+        assert_eq!(manipulation.distance, None);
+
+        manipulation.pending_distance = 10.0;
+        assert_eq!(manipulation.apply_pending_distance(1.0, &range), 3.0);
+        assert_eq!(manipulation.distance, Some(3.0));
+        assert_eq!(manipulation.pending_distance, 0.0);
+
+        manipulation.pending_distance = -10.0;
+        assert_eq!(manipulation.apply_pending_distance(3.0, &range), 0.5);
+        assert_eq!(manipulation.distance, Some(0.5));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    prelude::*,
+    verb::{Holding, SetVerb, Verb},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        PhysicsSchedule,
+        tick_held_props.in_set(AvianPickupSystem::TickTimers),
+    )
+    .add_observer(on_remove_held_prop);
+}
+
+fn tick_held_props(mut q_prop: Query<&mut HeldProp>, time: Res<Time>) {
+    for mut held_prop in q_prop.iter_mut() {
+        held_prop.duration += time.delta();
+    }
+}
+
+/// Releases the actor when its prop stops being held without the actor
+/// dropping or throwing it, e.g. because the prop was despawned.
+fn on_remove_held_prop(
+    trigger: Trigger<OnRemove, HeldProp>,
+    mut commands: Commands,
+    q_prop: Query<&HeldProp>,
+    q_actor: Query<&Holding>,
+) {
+    let prop = trigger.entity();
+    // Safety: We are removing a `HeldProp` component, so we know that the entity
+    // has one.
+    let actor = q_prop.get(prop).unwrap().actor;
+    let Ok(holding) = q_actor.get(actor) else {
+        // The actor already released the prop.
+        return;
+    };
+    if holding.0 != prop {
+        return;
+    }
+    commands.entity(actor).queue(SetVerb::new(Verb::Drop {
+        prop,
+        reason: PropDropReason::Lost,
+    }));
+}
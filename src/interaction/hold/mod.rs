@@ -1,6 +1,7 @@
 use crate::prelude::*;
 
 mod components;
+mod held_prop;
 mod on_add_holding;
 mod on_remove_holding;
 mod set_velocities;
@@ -21,6 +22,7 @@ pub(super) fn plugin(app: &mut App) {
         on_add_holding::plugin,
         on_remove_holding::plugin,
         components::plugin,
+        held_prop::plugin,
         update_error::plugin,
         update_targets::plugin,
         set_velocities::plugin,
@@ -35,5 +37,8 @@ enum HoldSystem {
 }
 
 pub(super) mod prelude {
-    pub(crate) use super::components::{HoldError, ShadowParams};
+    pub(crate) use super::components::{
+        ActiveHoldMode, HoldJoint, HoldManipulation, LastHold, PdControllerState, PendingGrabPoint,
+    };
+    pub use super::components::{HoldError, PlacementPreview, ShadowParams};
 }
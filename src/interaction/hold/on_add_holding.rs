@@ -1,4 +1,6 @@
-use super::prelude::HoldError;
+use super::prelude::{
+    ActiveHoldMode, HoldError, HoldJoint, PdControllerState, PendingGrabPoint, ShadowParams,
+};
 use crate::{math::GetBestGlobalTransform, prelude::*, prop::PrePickupRotation, verb::Holding};
 
 pub(super) fn plugin(app: &mut App) {
@@ -13,35 +15,80 @@ pub fn on_add_holding(
         &AvianPickupActor,
         &mut AvianPickupActorState,
         &mut HoldError,
+        &mut ShadowParams,
+        Option<&PendingGrabPoint>,
         &Holding,
     )>,
     q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
     mut q_prop: Query<(
+        &Position,
         &Rotation,
         &mut Mass,
         Option<&PickupMassOverride>,
         Option<&mut NonPickupMass>,
         Option<&mut PrePickupRotation>,
+        &mut RigidBody,
+        Option<&NonDynamicPropPolicyOverride>,
+        Option<&PickupGrabPoint>,
     )>,
+    mut w_picked_up: EventWriter<PropPickedUp>,
+    mut w_hold_lost: EventWriter<PropHoldLost>,
 ) {
     let actor = trigger.entity();
-    let Ok((config, mut state, mut hold_error, holding)) = q_actor.get_mut(actor) else {
+    let Ok((config, mut state, mut hold_error, mut shadow, pending_grab_point, holding)) =
+        q_actor.get_mut(actor)
+    else {
         error!("Actor entity was deleted or in an invalid state. Ignoring.");
         return;
     };
-    let actor_transform = q_actor_transform.get_best_global_transform(actor);
+    let actor_transform = q_actor_transform.get_aim_transform(actor, config);
     let prop = holding.0;
-    *state = AvianPickupActorState::Holding(prop);
-    commands.entity(prop).insert(HeldProp);
-    let Ok((rotation, mut mass, pickup_mass, non_pickup_mass, pre_pickup_rotation)) =
-        q_prop.get_mut(prop)
+    let Ok((
+        position,
+        rotation,
+        mut mass,
+        pickup_mass,
+        non_pickup_mass,
+        pre_pickup_rotation,
+        mut rigid_body,
+        policy_override,
+        grab_point,
+    )) = q_prop.get_mut(prop)
     else {
-        error!("Prop entity was deleted or in an invalid state. Ignoring.");
+        // The prop was despawned between finding it and picking it up.
+        *state = AvianPickupActorState::Idle;
+        commands.entity(actor).remove::<Holding>();
+        let event = PropHoldLost {
+            actor,
+            prop,
+            reason: HoldLostReason::PropDespawned,
+        };
+        w_hold_lost.send(event);
+        commands.trigger_targets(event, actor);
         return;
     };
+    *state = AvianPickupActorState::Holding(prop);
+    if pending_grab_point.is_some() {
+        commands.entity(actor).remove::<PendingGrabPoint>();
+    }
+    shadow.grab_offset = grab_point
+        .map(|grab_point| grab_point.0)
+        .or_else(|| {
+            pending_grab_point
+                .filter(|pending| pending.prop == prop)
+                .map(|pending| pending.offset)
+        })
+        .unwrap_or(Vec3::ZERO);
+    commands.entity(prop).insert(HeldProp {
+        actor,
+        duration: default(),
+    });
     let new_mass = pickup_mass
         .map(|m| m.0)
         .unwrap_or(config.hold.temporary_prop_mass);
+    // When the prop was taken from another actor, that actor already released
+    // it and restored its mass, as a prop cannot be held by two actors at once.
+    // So the current mass is always the one the prop has when not being held.
     if let Some(mut non_pickup_mass) = non_pickup_mass {
         non_pickup_mass.0 = mass.0
     } else {
@@ -59,18 +106,72 @@ pub fn on_add_holding(
             .insert(PrePickupRotation(actor_space_rotation));
     }
 
-    mass.set(Box::new(new_mass));
+    if !matches!(config.hold.mode, HoldMode::PdController { .. }) {
+        mass.set(Box::new(new_mass));
+    }
+    commands
+        .entity(actor)
+        .insert(ActiveHoldMode(config.hold.mode));
+    if matches!(config.hold.mode, HoldMode::PdController { .. }) {
+        commands.entity(actor).insert(PdControllerState::default());
+    }
+
+    let policy = policy_override
+        .map(|p| p.0)
+        .unwrap_or(config.pull.non_dynamic_props);
+    if policy == NonDynamicPropPolicy::MakeDynamic && !rigid_body.is_dynamic() {
+        commands
+            .entity(prop)
+            .insert(NonPickupRigidBody(*rigid_body));
+        *rigid_body = RigidBody::Dynamic;
+    }
+
+    if let HoldMode::Joint {
+        compliance,
+        linear_damping,
+        angular_damping,
+    } = config.hold.mode
+    {
+        let anchor = commands
+            .spawn((
+                Name::new("Avian Pickup Hold Anchor"),
+                Transform::from_translation(position.0).with_rotation(rotation.0),
+                // Inserting these right away so that the anchor can be moved
+                // before the physics engine initializes it.
+                *position,
+                *rotation,
+                LinearVelocity::ZERO,
+                AngularVelocity::ZERO,
+                RigidBody::Kinematic,
+            ))
+            .id();
+        let joint = commands
+            .spawn((
+                Name::new("Avian Pickup Hold Joint"),
+                FixedJoint::new(anchor, prop)
+                    .with_compliance(compliance)
+                    .with_linear_velocity_damping(linear_damping)
+                    .with_angular_velocity_damping(angular_damping),
+            ))
+            .id();
+        commands.entity(actor).insert(HoldJoint { anchor, joint });
+    }
     // The original code also does some damping stuff, but then deactivates
     // drag? Seems like a no-op to me
 
     hold_error.reset();
 
+    let event = PropPickedUp { actor, prop };
+    w_picked_up.send(event);
+    commands.trigger_targets(event, [prop, actor]);
+
     // The original code now does some stuff with `AlignAngles`, but it only
     // does so when `m_angleAlignment != 0`, which does not seem to be the
     // case for HL2 deathmatch, judging by the code? Anyhoot, per
     // discussions on Discord, that code seems to align the prop to
     // the coordinate axes if it is closer than 30 degrees to them.
-    // Does not seem to be that useful.
+    // We do that every update in `set_targets` if
+    // `AvianPickupActorHoldConfig::alignment` is set.
 }
 
 /// TransformAnglesToPlayerSpace
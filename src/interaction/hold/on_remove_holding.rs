@@ -1,4 +1,8 @@
-use crate::{prelude::*, verb::Holding};
+use super::prelude::{ActiveHoldMode, HoldJoint, HoldManipulation, LastHold, PdControllerState};
+use crate::{
+    prelude::*,
+    verb::{Dropping, Holding, Throwing},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_observer(on_remove_holding);
@@ -7,24 +11,111 @@ pub(super) fn plugin(app: &mut App) {
 fn on_remove_holding(
     trigger: Trigger<OnRemove, Holding>,
     mut commands: Commands,
-    q_actor: Query<&Holding>,
-    mut q_prop: Query<(&mut Mass, Option<&NonPickupMass>, Has<HeldProp>)>,
+    q_actor: Query<(&Holding, Option<&HoldJoint>, Option<&PdControllerState>)>,
+    mut q_prop: Query<(
+        &mut Mass,
+        Option<&NonPickupMass>,
+        Option<&HeldProp>,
+        &mut RigidBody,
+        Option<&NonPickupRigidBody>,
+        Option<&mut ExternalForce>,
+        Option<&mut ExternalTorque>,
+    )>,
 ) {
     // Safety: We are removing a `Holding` component, so we know that the entity has
     // one.
-    let holding = q_actor.get(trigger.entity()).unwrap();
+    let actor = trigger.entity();
+    let (holding, hold_joint, pd_state) = q_actor.get(actor).unwrap();
     let prop = holding.0;
-    let Ok((mut mass, non_pickup_mass, has_held_marker)) = q_prop.get_mut(prop) else {
-        error!("Prop entity was deleted or in an invalid state. Ignoring.");
+    if let Some(hold_joint) = hold_joint {
+        commands.entity(hold_joint.joint).despawn_recursive();
+        commands.entity(hold_joint.anchor).despawn_recursive();
+    }
+    // `Holding` is also removed when the actor is despawned, so don't panic if
+    // it's gone.
+    commands.queue(move |world: &mut World| {
+        if let Ok(mut actor) = world.get_entity_mut(actor) {
+            // A `LastHold` that was not consumed by throwing or dropping would
+            // otherwise be reported for the next prop.
+            actor.remove::<(
+                HoldJoint,
+                ActiveHoldMode,
+                PdControllerState,
+                ThrowCharge,
+                LastHold,
+                PlacementPreview,
+            )>();
+            if let Some(mut manipulation) = actor.get_mut::<HoldManipulation>() {
+                // The next prop starts out unmanipulated.
+                *manipulation = default();
+            }
+        }
+    });
+    let Ok((
+        mut mass,
+        non_pickup_mass,
+        held_prop,
+        mut rigid_body,
+        non_pickup_rigid_body,
+        force,
+        torque,
+    )) = q_prop.get_mut(prop)
+    else {
+        // The prop was despawned while being held, so there is nothing to restore.
         return;
     };
-    if !has_held_marker {
-        error!(
-            "A held prop that is no longer being held was not actually marked as held. This is supremely weird. Ignoring."
-        );
+    if let Some(pd_state) = pd_state {
+        // Don't leave the last controller output acting on the prop, but keep
+        // whatever else was applied to it. Non-persistent forces are cleared
+        // after each physics step anyways.
+        if let Some(mut force) = force.filter(|force| force.persistent) {
+            force.apply_force(-pd_state.force);
+        }
+        if let Some(mut torque) = torque.filter(|torque| torque.persistent) {
+            torque.apply_torque(-pd_state.torque);
+        }
+    }
+    if held_prop.is_some_and(|held| held.actor != actor) {
+        // Another actor took the prop away from us, so it's theirs to restore.
         return;
     }
-    commands.entity(prop).remove::<HeldProp>();
+    let last_hold = LastHold {
+        prop,
+        duration: held_prop.map(|held| held.duration).unwrap_or_default(),
+        temporary_mass: mass.0,
+    };
+    commands.queue(move |world: &mut World| {
+        let Ok(mut actor) = world.get_entity_mut(actor) else {
+            return;
+        };
+        // Only throwing and dropping consume the last hold.
+        let released = actor
+            .get::<Throwing>()
+            .is_some_and(|throwing| throwing.prop == prop)
+            || actor
+                .get::<Dropping>()
+                .is_some_and(|dropping| dropping.prop == prop);
+        if released {
+            actor.insert(last_hold);
+        }
+    });
+    if held_prop.is_some() {
+        commands
+            .entity(prop)
+            .queue(move |mut prop: EntityWorldMut| {
+                // Another actor might have taken the prop in the meantime.
+                if prop
+                    .get::<HeldProp>()
+                    .is_some_and(|held| held.actor == actor)
+                {
+                    prop.remove::<HeldProp>();
+                }
+            });
+    }
+    if let Some(non_pickup_rigid_body) = non_pickup_rigid_body {
+        *rigid_body = non_pickup_rigid_body.0;
+        commands.entity(prop).remove::<NonPickupRigidBody>();
+    }
     let Some(non_pickup_mass) = non_pickup_mass else {
         error!(
             "A held prop that is no longer being held failed to get its pre-pickup mass back. Ignoring."
@@ -33,3 +124,170 @@ fn on_remove_holding(
     };
     mass.set(Box::new(non_pickup_mass.0));
 }
+
+#[cfg(test)]
+mod test {
+    use bevy::ecs::system::RunSystemOnce as _;
+
+    use super::*;
+    use crate::{
+        interaction::{drop::drop, hold::on_add_holding::on_add_holding},
+        verb::{SetVerb, Verb},
+    };
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Events<PropHoldLost>>();
+        world.add_observer(on_remove_holding);
+        world
+    }
+
+    /// Spawns an actor holding a prop, with a placement preview and a stale
+    /// last hold of another prop.
+    fn spawn_holding_actor(world: &mut World) -> (Entity, Entity) {
+        let prop = world
+            .spawn((RigidBody::Dynamic, Mass(1.0), NonPickupMass(2.0)))
+            .id();
+        let stale_prop = world.spawn_empty().id();
+        let actor = world
+            .spawn((
+                AvianPickupActorState::Holding(prop),
+                Holding(prop),
+                PlacementPreview {
+                    transform: Transform::from_xyz(1.0, 2.0, 3.0),
+                    valid: true,
+                },
+                LastHold {
+                    prop: stale_prop,
+                    duration: default(),
+                    temporary_mass: 1.0,
+                },
+            ))
+            .id();
+        world.entity_mut(prop).insert(HeldProp {
+            actor,
+            duration: default(),
+        });
+        (actor, prop)
+    }
+
+    #[test]
+    fn dropping_records_last_hold_and_placement() {
+        let mut world = world();
+        let (actor, prop) = spawn_holding_actor(&mut world);
+
+        SetVerb::new(Verb::Drop {
+            prop,
+            reason: PropDropReason::Placed,
+        })
+        .apply(actor, &mut world);
+        world.flush();
+
+        let actor = world.entity(actor);
+        assert_eq!(actor.get::<LastHold>().map(|l| l.prop), Some(prop));
+        assert!(!actor.contains::<PlacementPreview>());
+        let dropping = actor.get::<Dropping>().unwrap();
+        assert_eq!(dropping.placement, Some(Transform::from_xyz(1.0, 2.0, 3.0)));
+        assert_eq!(world.get::<Mass>(prop).map(|m| m.0), Some(2.0));
+    }
+
+    #[test]
+    fn manipulation_is_reset_on_drop() {
+        let mut world = world();
+        let (actor, prop) = spawn_holding_actor(&mut world);
+        world.entity_mut(actor).insert(HoldManipulation {
+            rotation: Some(Quat::from_rotation_y(1.0)),
+            distance: Some(2.0),
+            ..default()
+        });
+
+        SetVerb::new(Verb::Drop {
+            prop,
+            reason: PropDropReason::Player,
+        })
+        .apply(actor, &mut world);
+        world.flush();
+
+        let manipulation = world.get::<HoldManipulation>(actor).unwrap();
+        assert_eq!(manipulation.rotation, None);
+        assert_eq!(manipulation.distance, None);
+    }
+
+    #[test]
+    fn releasing_without_dropping_clears_stale_state() {
+        let mut world = world();
+        let (actor, _prop) = spawn_holding_actor(&mut world);
+
+        world.entity_mut(actor).remove::<Holding>();
+        world.flush();
+
+        let actor = world.entity(actor);
+        assert!(!actor.contains::<LastHold>());
+        assert!(!actor.contains::<PlacementPreview>());
+    }
+
+    #[test]
+    fn contested_prop_is_handed_over_to_stronger_actor() {
+        let mut world = world();
+        world.init_resource::<Events<PropPickedUp>>();
+        world.init_resource::<Events<PropDropped>>();
+        world.init_resource::<Events<PropPullCancelled>>();
+        world.add_observer(on_add_holding);
+        let mut spawn_actor = |strength, temporary_prop_mass| {
+            let actor = world
+                .spawn((
+                    AvianPickupActor {
+                        pull: AvianPickupActorPullConfig {
+                            strength,
+                            contest: PropContestPolicy::StrongerActor,
+                            ..default()
+                        },
+                        hold: AvianPickupActorHoldConfig {
+                            temporary_prop_mass,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    GlobalTransform::default(),
+                ))
+                .id();
+            // Let the actor insert its required components.
+            world.flush();
+            actor
+        };
+        let holder = spawn_actor(1.0, 1.0);
+        let actor = spawn_actor(2.0, 3.0);
+        let prop = world
+            .spawn((
+                RigidBody::Dynamic,
+                Mass(5.0),
+                Position::default(),
+                Rotation::default(),
+                LinearVelocity::default(),
+                AngularVelocity::default(),
+            ))
+            .id();
+
+        world.commands().entity(holder).pickup(prop);
+        world.flush();
+        assert_eq!(world.get::<Mass>(prop).map(|m| m.0), Some(1.0));
+
+        world.commands().entity(actor).pickup(prop);
+        world.flush();
+        world.run_system_once(drop).unwrap();
+
+        assert!(!world.entity(holder).contains::<Holding>());
+        assert_eq!(world.get::<Holding>(actor).map(|h| h.0), Some(prop));
+        assert_eq!(world.get::<HeldProp>(prop).map(|h| h.actor), Some(actor));
+        // The holder restored the original mass before the new holder
+        // remembered it and applied its own temporary mass.
+        assert_eq!(world.get::<NonPickupMass>(prop).map(|m| m.0), Some(5.0));
+        assert_eq!(world.get::<Mass>(prop).map(|m| m.0), Some(3.0));
+        let dropped: Vec<_> = world
+            .resource::<Events<PropDropped>>()
+            .iter_current_update_events()
+            .map(|event| (event.actor, event.prop, event.reason, event.mass))
+            .collect();
+        assert_eq!(dropped, [(holder, prop, PropDropReason::Contested, 5.0)]);
+    }
+}
@@ -1,7 +1,15 @@
 use std::f32::consts::{PI, TAU};
 
-use super::{prelude::ShadowParams, HoldSystem};
-use crate::{prelude::*, verb::Holding};
+use avian3d::math::Scalar;
+
+use super::{
+    prelude::{ActiveHoldMode, HoldJoint, PdControllerState, ShadowParams},
+    HoldSystem,
+};
+use crate::{
+    prelude::*,
+    verb::{Holding, SetVerb, Verb},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
@@ -12,6 +20,7 @@ pub(super) fn plugin(app: &mut App) {
 
 /// CGrabController::Simulate
 fn set_velocities(
+    mut commands: Commands,
     time: Res<Time>,
     mut q_prop: Query<(
         &mut LinearVelocity,
@@ -19,7 +28,16 @@ fn set_velocities(
         &Position,
         &Rotation,
     )>,
-    mut q_actor: Query<(&ShadowParams, &Holding, &AvianPickupActor)>,
+    mut q_prop_forces: Query<(&mut ExternalForce, &mut ExternalTorque)>,
+    mut q_actor: Query<(
+        Entity,
+        &ShadowParams,
+        &Holding,
+        &AvianPickupActor,
+        Option<&HoldJoint>,
+        Option<&ActiveHoldMode>,
+        Option<&mut PdControllerState>,
+    )>,
 ) {
     // Valve uses CGrabController::Simulate, which does *a lot* of stuff,
     // but from testing, it seems like this does the job pretty much identically,
@@ -27,10 +45,21 @@ fn set_velocities(
     // check out the commit aa51b2bc4dbc52049476135ba146b3ba143b681a
     let dt = time.delta_secs();
     let inv_dt = dt.recip();
-    for (shadow, holding, actor) in q_actor.iter_mut() {
-        let prop = holding.0;
-        let Ok((mut velocity, mut angvel, position, rotation)) = q_prop.get_mut(prop) else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
+    for (actor_entity, shadow, holding, actor, hold_joint, active_mode, pd_state) in
+        q_actor.iter_mut()
+    {
+        // When holding with a joint, we move the anchor and let the joint
+        // drag the prop along.
+        let driven = hold_joint
+            .map(|hold_joint| hold_joint.anchor)
+            .unwrap_or(holding.0);
+        let Ok((mut velocity, mut angvel, position, rotation)) = q_prop.get_mut(driven) else {
+            commands
+                .entity(actor_entity)
+                .queue(SetVerb::new(Verb::Drop {
+                    prop: holding.0,
+                    reason: PropDropReason::Lost,
+                }));
             continue;
         };
 
@@ -42,6 +71,63 @@ fn set_velocities(
         let angle = if angle > PI { angle - TAU } else { angle };
         let delta_rotation_scaled_axis = axis * angle;
 
+        if let (
+            Some(&ActiveHoldMode(HoldMode::PdController {
+                linear_stiffness,
+                linear_damping,
+                angular_stiffness,
+                angular_damping,
+                max_force,
+                max_torque,
+                max_overload_duration,
+            })),
+            Some(mut pd_state),
+        ) = (active_mode, pd_state)
+        {
+            let Ok((mut force, mut torque)) = q_prop_forces.get_mut(driven) else {
+                error!("Prop entity was deleted or in an invalid state. Ignoring.");
+                continue;
+            };
+            // The prop keeps its real mass, so we deliberately don't scale
+            // by it: heavier props accelerate slower and lag behind.
+            let target_force = delta_position * linear_stiffness - velocity.0 * linear_damping;
+            let target_torque =
+                delta_rotation_scaled_axis * angular_stiffness - angvel.0 * angular_damping;
+            let Some((target_force, target_torque)) = limit_pd_output(
+                &mut pd_state.overload_time,
+                (target_force, target_torque),
+                (max_force, max_torque),
+                max_overload_duration,
+                dt,
+            ) else {
+                commands
+                    .entity(actor_entity)
+                    .queue(SetVerb::new(Verb::Drop {
+                        prop: holding.0,
+                        reason: PropDropReason::ForceLimitExceeded,
+                    }));
+                continue;
+            };
+            // Only replace our own contribution so that forces applied by the
+            // user keep acting on the prop. Non-persistent forces are cleared
+            // every physics step, so there is nothing to replace there.
+            let previous_force = if force.persistent {
+                pd_state.force
+            } else {
+                Vec3::ZERO
+            };
+            let previous_torque = if torque.persistent {
+                pd_state.torque
+            } else {
+                Vec3::ZERO
+            };
+            force.apply_force(target_force - previous_force);
+            torque.apply_torque(target_torque - previous_torque);
+            pd_state.force = target_force;
+            pd_state.torque = target_torque;
+            continue;
+        }
+
         // This is used for a bit of easing. We don't need to be careful about
         // things like overshooting as we are in a fixed timestep.
         // Negative because the dt is already inverted
@@ -56,6 +142,30 @@ fn set_velocities(
     }
 }
 
+/// Clamps the force and torque of the PD controller to their maximum.
+/// Returns `None` if they have been exceeding it for longer than
+/// `max_overload_duration`, which means that the prop should be dropped.
+fn limit_pd_output(
+    overload_time: &mut Scalar,
+    (force, torque): (Vec3, Vec3),
+    (max_force, max_torque): (Scalar, Scalar),
+    max_overload_duration: Scalar,
+    dt: Scalar,
+) -> Option<(Vec3, Vec3)> {
+    if force.length() > max_force || torque.length() > max_torque {
+        *overload_time += dt;
+        if *overload_time > max_overload_duration {
+            return None;
+        }
+    } else {
+        *overload_time = 0.0;
+    }
+    Some((
+        force.clamp_length_max(max_force),
+        torque.clamp_length_max(max_torque),
+    ))
+}
+
 fn zero_if_near_zero(vec: Vec3) -> Vec3 {
     // This seems large, but since we multiply by the inverse of the delta time,
     // it's actually quite small.
@@ -66,3 +176,66 @@ fn zero_if_near_zero(vec: Vec3) -> Vec3 {
         vec
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LIMITS: (Scalar, Scalar) = (2000.0, 200.0);
+    const MAX_OVERLOAD_DURATION: Scalar = 0.5;
+    const DT: Scalar = 1.0 / 64.0;
+
+    #[test]
+    fn pd_controller_output_is_clamped_before_dropping() {
+        let mut overload_time = 0.0;
+        // A prop that was just picked up from far away needs a lot of force.
+        let overloaded = (Vec3::X * 10_000.0, Vec3::Y * 1_000.0);
+        let (force, torque) = limit_pd_output(
+            &mut overload_time,
+            overloaded,
+            LIMITS,
+            MAX_OVERLOAD_DURATION,
+            DT,
+        )
+        .expect("A single overloaded step should not drop the prop");
+        assert!((force.length() - LIMITS.0).abs() < 1e-2);
+        assert!((torque.length() - LIMITS.1).abs() < 1e-2);
+
+        // Getting back within the limits resets the overload.
+        let relaxed = (Vec3::X * 10.0, Vec3::Y);
+        let output = limit_pd_output(
+            &mut overload_time,
+            relaxed,
+            LIMITS,
+            MAX_OVERLOAD_DURATION,
+            DT,
+        );
+        assert_eq!(output, Some(relaxed));
+        assert_eq!(overload_time, 0.0);
+    }
+
+    #[test]
+    fn pd_controller_drops_after_sustained_overload() {
+        let mut overload_time = 0.0;
+        let overloaded = (Vec3::X * 10_000.0, Vec3::ZERO);
+        let steps_until_drop = (MAX_OVERLOAD_DURATION / DT) as usize + 1;
+        for _ in 0..steps_until_drop - 1 {
+            assert!(limit_pd_output(
+                &mut overload_time,
+                overloaded,
+                LIMITS,
+                MAX_OVERLOAD_DURATION,
+                DT
+            )
+            .is_some());
+        }
+        let output = limit_pd_output(
+            &mut overload_time,
+            overloaded,
+            LIMITS,
+            MAX_OVERLOAD_DURATION,
+            DT,
+        );
+        assert_eq!(output, None);
+    }
+}
@@ -1,5 +1,8 @@
 use super::{prelude::*, HoldSystem};
-use crate::{prelude::*, verb::Holding};
+use crate::{
+    prelude::*,
+    verb::{Holding, SetVerb, Verb},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
@@ -10,19 +13,23 @@ pub(super) fn plugin(app: &mut App) {
 
 /// CGrabController::ComputeError(),
 pub fn update_error(
+    mut commands: Commands,
     time: Res<Time>,
     q_prop: Query<&Position>,
-    mut q_actor: Query<(&mut HoldError, &ShadowParams, &Holding)>,
+    mut q_actor: Query<(Entity, &mut HoldError, &ShadowParams, &Holding)>,
 ) {
     let dt = time.delta_secs();
-    for (mut hold_error, shadow, holding) in q_actor.iter_mut() {
+    for (actor, mut hold_error, shadow, holding) in q_actor.iter_mut() {
         let prop = holding.0;
         hold_error.error_time += dt;
         if hold_error.error_time <= 0.0 {
             continue;
         }
         let Ok(position) = q_prop.get(prop) else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
+            commands.entity(actor).queue(SetVerb::new(Verb::Drop {
+                prop,
+                reason: PropDropReason::Lost,
+            }));
             continue;
         };
         let mut error = (position.0 - shadow.target_position).length();
@@ -1,10 +1,13 @@
-use avian3d::sync::ancestor_marker::AncestorMarker;
+use avian3d::{math::Scalar, sync::ancestor_marker::AncestorMarker};
 
 use super::{prelude::*, HoldSystem};
 use crate::{
-    math::{rigid_body_compound_collider, GetBestGlobalTransform as _},
+    math::{
+        rigid_body_collider_entities, rigid_body_compound_collider, GetBestGlobalTransform as _,
+    },
     prelude::*,
     prop::PrePickupRotation,
+    slot::SlotOwner,
     verb::{Holding, SetVerb, Verb},
 };
 
@@ -21,10 +24,13 @@ fn set_targets(
         &AvianPickupActor,
         &HoldError,
         &mut ShadowParams,
+        &mut HoldManipulation,
+        Option<&mut PlacementPreview>,
         &Holding,
     )>,
     q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
     mut q_prop: Query<(
+        &Position,
         &Rotation,
         Option<&PrePickupRotation>,
         Option<&PreferredPickupRotation>,
@@ -34,19 +40,26 @@ fn set_targets(
 
     q_collider_ancestor: Query<&Children, With<AncestorMarker<ColliderMarker>>>,
     q_collider: Query<(&Transform, &Collider, Option<&CollisionLayers>)>,
+    q_slot_owner: Query<&SlotOwner>,
+    q_slots: Query<&AvianPickupSlots>,
 ) {
-    let max_error = 0.3048; // 12 inches in the source engine
-    for (actor, config, hold_error, mut shadow, holding) in q_actor.iter_mut() {
+    for (actor, config, hold_error, mut shadow, mut manipulation, placement, holding) in
+        q_actor.iter_mut()
+    {
         let prop = holding.0;
-        if hold_error.error > max_error {
-            commands
-                .entity(actor)
-                .queue(SetVerb::new(Verb::Drop { prop, forced: true }));
+        if hold_error.error > config.hold.max_error {
+            commands.entity(actor).queue(SetVerb::new(Verb::Drop {
+                prop,
+                reason: PropDropReason::HoldErrorExceeded,
+            }));
             continue;
         }
-        let actor_transform = q_actor_transform.get_best_global_transform(actor);
+        let actor_transform = q_actor_transform.get_aim_transform(actor, config);
+        let anchor_entity = config.hold_anchor_entity(actor);
+        let anchor_transform = q_actor_transform.get_hold_anchor_transform(actor, config);
 
         let Ok((
+            prop_position,
             prop_rotation,
             pre_pickup_rotation,
             preferred_rotation,
@@ -54,9 +67,24 @@ fn set_targets(
             clamp_pitch,
         )) = q_prop.get_mut(prop)
         else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
+            commands.entity(actor).queue(SetVerb::new(Verb::Drop {
+                prop,
+                reason: PropDropReason::Lost,
+            }));
             continue;
         };
+        let grab_point = prop_position.0 + prop_rotation.0 * shadow.grab_offset;
+        let out_of_range = config.hold.break_distance.is_some_and(|break_distance| {
+            anchor_transform.translation.distance_squared(grab_point)
+                > break_distance * break_distance
+        });
+        if out_of_range {
+            commands.entity(actor).queue(SetVerb::new(Verb::Drop {
+                prop,
+                reason: PropDropReason::OutOfRange,
+            }));
+            continue;
+        }
         let pitch_range = clamp_pitch
             .map(|c| &c.0)
             .unwrap_or(&config.hold.pitch_range);
@@ -78,22 +106,38 @@ fn set_targets(
             error!("Held prop does not have a collider in its hierarchy. Ignoring.");
             continue;
         };
-        let prop_radius_wrt_direction =
-            collide_get_extent(&prop_collider, Vec3::ZERO, prop_rotation.0, -forward);
-        let actor_collider = rigid_body_compound_collider(
-            actor,
+        // The prop is held at its grab point, so that is where its extent
+        // towards the actor starts.
+        let prop_radius_wrt_direction = collide_get_extent(
+            &prop_collider,
+            prop_rotation.0 * shadow.grab_offset,
+            prop_rotation.0,
+            -forward,
+        );
+        // Slots share the body of their owner, e.g. two hands of the same player.
+        let body = AvianPickupSlots::find_owner(actor, &q_slot_owner, &q_slots)
+            .map(|(owner, _slots)| owner)
+            .unwrap_or(actor);
+        let body_collider = rigid_body_compound_collider(
+            body,
             &q_collider_ancestor,
             &q_collider,
             &config.actor_filter,
         );
-        let actor_radius_wrt_direction = if let Some(actor_collider) = actor_collider {
-            let min_distance_to_not_penetrate = collide_get_extent(
-                &actor_collider,
-                Vec3::ZERO,
-                actor_transform.rotation,
-                forward,
-            );
-            min_distance_to_not_penetrate.max(config.hold.min_distance)
+        let actor_radius_wrt_direction = if let Some(body_collider) = body_collider {
+            let body_transform = q_actor_transform.get_best_global_transform(body);
+            // The extent is measured from the hold anchor, which is only
+            // different from the body's origin for slots or custom anchors.
+            let origin = anchor_transform.translation - body_transform.translation;
+            let is_inside_body = body == anchor_entity
+                || body_collider.contains_point(Vec3::ZERO, body_transform.rotation, origin);
+            if is_inside_body {
+                let min_distance_to_not_penetrate =
+                    collide_get_extent(&body_collider, origin, body_transform.rotation, forward);
+                min_distance_to_not_penetrate.max(config.hold.min_distance)
+            } else {
+                config.hold.min_distance
+            }
         } else {
             config.hold.min_distance
         };
@@ -101,9 +145,13 @@ fn set_targets(
         let min_distance = prop_radius_wrt_direction + actor_radius_wrt_direction;
         // The 2013 code now additionally does `min_distance = (min_distance * 2) + 24
         // inches` That seems straight up bizarre, so I refuse to do that.
-        let preferred_distance = preferred_distance
-            .map(|d| d.0)
-            .unwrap_or(config.hold.preferred_distance);
+        let preferred_distance = manipulation.distance.unwrap_or_else(|| {
+            preferred_distance
+                .map(|d| d.0)
+                .unwrap_or(config.hold.preferred_distance)
+        });
+        let preferred_distance = manipulation
+            .apply_pending_distance(preferred_distance, &config.hold.manipulation_distance_range);
         // The 2013 code does `max_distance = preferred_distance + min_distance`
         // which means that `preferred_distance` is the distance between the prop's
         // edge and the actors's edge. Expect psyche, actually `min_distance` gets
@@ -112,8 +160,9 @@ fn set_targets(
         // actor's origins if possible instead.
         let max_distance = preferred_distance.max(min_distance);
 
-        let Some(actor_space_rotation) = preferred_rotation
-            .map(|preferred| preferred.0)
+        let Some(actor_space_rotation) = manipulation
+            .rotation
+            .or_else(|| preferred_rotation.map(|preferred| preferred.0))
             .or_else(|| pre_pickup_rotation.map(|pre| pre.0))
         else {
             error!("Held prop does not have a preferred or pre-pickup rotation. Ignoring.");
@@ -126,6 +175,35 @@ fn set_targets(
         let clamped_actor_transform = actor_transform.with_rotation(clamped_rotation);
         let target_rotation =
             prop_rotation_from_actor_space(actor_space_rotation, clamped_actor_transform);
+        let align = |rotation: Quat, manipulated: bool| {
+            config.hold.alignment.map_or(rotation, |alignment| {
+                align_rotation(rotation, clamped_rotation, alignment, manipulated)
+            })
+        };
+        let target_rotation =
+            if manipulation.pending_yaw != 0.0 || manipulation.pending_pitch != 0.0 {
+                let speed = config.hold.manipulation_rotation_speed;
+                // Yaw around the world's up axis, pitch around the actor's right axis.
+                let yaw = Quat::from_rotation_y(manipulation.pending_yaw * speed);
+                let pitch = Quat::from_axis_angle(
+                    clamped_actor_transform.right().into(),
+                    manipulation.pending_pitch * speed,
+                );
+                // Start from what the player sees when manipulating for the first time.
+                let base_rotation = if manipulation.rotation.is_some() {
+                    target_rotation
+                } else {
+                    align(target_rotation, false)
+                };
+                let target_rotation = (pitch * yaw * base_rotation).normalize();
+                manipulation.pending_yaw = 0.0;
+                manipulation.pending_pitch = 0.0;
+                manipulation.rotation = Some(clamped_rotation.inverse() * target_rotation);
+                target_rotation
+            } else {
+                target_rotation
+            };
+        let target_rotation = align(target_rotation, manipulation.rotation.is_some());
 
         shadow.target_rotation = target_rotation;
 
@@ -141,14 +219,14 @@ fn set_targets(
         let mut terrain_filter = config.obstacle_filter.clone();
         terrain_filter.excluded_entities.insert(prop);
         let shape_cast_config = ShapeCastConfig::from_max_distance(max_cast_toi);
-        let terrain_hit = spatial_query.cast_shape(
+        let terrain_hit = cast_held_prop(
+            &spatial_query,
             &prop_collider,
-            actor_transform.translation,
+            anchor_transform.translation,
             target_rotation,
+            shadow.grab_offset,
             forward,
             &shape_cast_config,
-            //max_cast_toi,
-            //true,
             &terrain_filter,
         );
         let distance = if let Some(terrain_hit) = terrain_hit {
@@ -168,9 +246,142 @@ fn set_targets(
         // Pretty sure we don't need to go through the CalcClosestPointOnLine song and
         // dance since we already have made sure that the prop has a sensible minimum
         // distance
-        let target_position = actor_transform.translation + forward * distance;
-        shadow.target_position = target_position;
+        let target_position = anchor_transform.translation + forward * distance;
+        // Place the prop so that its grab point ends up at the target and it
+        // rotates around that point.
+        shadow.target_position = target_position - target_rotation * shadow.grab_offset;
+
+        let Some(placement_config) = config.hold.placement else {
+            continue;
+        };
+        let mut placement_filter = terrain_filter;
+        placement_filter
+            .excluded_entities
+            .extend(rigid_body_collider_entities(
+                body,
+                &q_collider_ancestor,
+                &q_collider,
+                &config.actor_filter,
+            ));
+        // The prop would otherwise always overlap with its own child colliders,
+        // no matter which layers they are on.
+        placement_filter
+            .excluded_entities
+            .extend(rigid_body_collider_entities(
+                prop,
+                &q_collider_ancestor,
+                &q_collider,
+                &SpatialQueryFilter::default(),
+            ));
+        let preview = find_placement(
+            &spatial_query,
+            &prop_collider,
+            actor_transform,
+            target_rotation,
+            shadow.grab_offset,
+            placement_config,
+            &placement_filter,
+        );
+        match (preview, placement) {
+            (Some(preview), Some(mut placement)) => *placement = preview,
+            (Some(preview), None) => {
+                commands.entity(actor).insert(preview);
+            }
+            (None, Some(_)) => {
+                commands.entity(actor).remove::<PlacementPreview>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Casts the held prop along `direction` with its grab point starting at
+/// `anchor`, so that the hit distance is the one of the grab point.
+fn cast_held_prop(
+    spatial_query: &SpatialQuery,
+    prop_collider: &Collider,
+    anchor: Vec3,
+    rotation: Quat,
+    grab_offset: Vec3,
+    direction: Dir3,
+    config: &ShapeCastConfig,
+    filter: &SpatialQueryFilter,
+) -> Option<ShapeHitData> {
+    spatial_query.cast_shape(
+        prop_collider,
+        anchor - rotation * grab_offset,
+        rotation,
+        direction,
+        config,
+        filter,
+    )
+}
+
+/// Casts the held prop along the actor's view direction to find a surface to
+/// place it on. Like when holding it, the prop's grab point follows the view
+/// direction.
+fn find_placement(
+    spatial_query: &SpatialQuery,
+    prop_collider: &Collider,
+    actor_transform: Transform,
+    rotation: Quat,
+    grab_offset: Vec3,
+    placement: HoldPlacement,
+    filter: &SpatialQueryFilter,
+) -> Option<PlacementPreview> {
+    let origin = actor_transform.translation;
+    let direction = actor_transform.forward();
+    let config = ShapeCastConfig::from_max_distance(placement.max_distance);
+    let cast = |rotation: Quat| {
+        cast_held_prop(
+            spatial_query,
+            prop_collider,
+            origin,
+            rotation,
+            grab_offset,
+            direction,
+            &config,
+            filter,
+        )
+    };
+    let mut hit = cast(rotation)?;
+    let mut rotation = rotation;
+    // `normal2` is the normal of the surface that was hit, `normal1` would be
+    // the one of the prop itself.
+    if placement.align_to_surface && hit.normal2.is_normalized() {
+        rotation = align_up_to_normal(rotation, hit.normal2);
+        // The tilted prop touches the surface at a different distance.
+        hit = cast(rotation).unwrap_or(hit);
     }
+    let translation = placed_translation(
+        origin - rotation * grab_offset,
+        direction,
+        hit.distance,
+        hit.normal2,
+    );
+    let valid = hit.distance > 0.0
+        && spatial_query
+            .shape_intersections(prop_collider, translation, rotation, filter)
+            .is_empty();
+    Some(PlacementPreview {
+        transform: Transform::from_translation(translation).with_rotation(rotation),
+        valid,
+    })
+}
+
+/// Rotates the prop so that its up axis points along the surface normal.
+fn align_up_to_normal(rotation: Quat, normal: Vec3) -> Quat {
+    let up = rotation * Vec3::Y;
+    (Quat::from_rotation_arc(up, normal) * rotation).normalize()
+}
+
+/// Where the prop ends up when casting it from `origin` hit a surface after
+/// `distance`.
+fn placed_translation(origin: Vec3, direction: Dir3, distance: Scalar, normal: Vec3) -> Vec3 {
+    // Keep a small gap so that resting on the surface does not count as
+    // overlapping it.
+    const SKIN: Scalar = 0.01;
+    origin + direction * distance + normal * SKIN
 }
 
 /// The original code gets the support point of the collider in the direction,
@@ -222,10 +433,163 @@ fn prop_rotation_from_actor_space(rot: Quat, actor: Transform) -> Quat {
     Quat::from_affine3(&out_affine)
 }
 
+/// AlignAngles
+fn align_rotation(
+    rotation: Quat,
+    actor_rotation: Quat,
+    alignment: HoldAlignment,
+    manipulated: bool,
+) -> Quat {
+    let reference = match alignment.space {
+        AlignmentSpace::World => Quat::IDENTITY,
+        AlignmentSpace::Actor => actor_rotation,
+    };
+    let local_rotation = reference.inverse() * rotation;
+    let aligned = match alignment.increment {
+        Some(increment) if manipulated => snap_to_increment(local_rotation, increment),
+        _ => snap_to_axes(local_rotation, alignment.threshold),
+    };
+    reference * aligned
+}
+
+/// Snaps the rotation to the closest axis-aligned rotation if it is within
+/// `threshold` radians of it.
+fn snap_to_axes(rotation: Quat, threshold: Scalar) -> Quat {
+    axis_aligned_rotations()
+        .max_by(|a, b| a.dot(rotation).abs().total_cmp(&b.dot(rotation).abs()))
+        .filter(|closest| closest.angle_between(rotation) <= threshold)
+        .unwrap_or(rotation)
+}
+
+/// All 24 rotations that map the coordinate axes onto the coordinate axes.
+fn axis_aligned_rotations() -> impl Iterator<Item = Quat> {
+    const AXES: [Vec3; 6] = [
+        Vec3::X,
+        Vec3::NEG_X,
+        Vec3::Y,
+        Vec3::NEG_Y,
+        Vec3::Z,
+        Vec3::NEG_Z,
+    ];
+    AXES.into_iter().flat_map(|x| {
+        AXES.into_iter()
+            .filter(move |y| x.dot(*y) == 0.0)
+            .map(move |y| Quat::from_mat3(&Mat3::from_cols(x, y, x.cross(y))))
+    })
+}
+
+fn snap_to_increment(rotation: Quat, increment: Scalar) -> Quat {
+    if increment <= 0.0 {
+        return rotation;
+    }
+    let snap = |angle: Scalar| (angle / increment).round() * increment;
+    let (yaw, pitch, roll) = rotation.to_euler(EulerRot::YXZ);
+    Quat::from_euler(EulerRot::YXZ, snap(yaw), snap(pitch), snap(roll))
+}
+
 #[cfg(test)]
 mod test {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use bevy::{ecs::system::RunSystemOnce, scene::ScenePlugin};
+
     use super::*;
 
+    fn physics_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            PhysicsPlugins::default(),
+        ))
+        .init_asset::<Mesh>();
+        app.finish();
+        app.cleanup();
+        app
+    }
+
+    fn spawn_static_box(app: &mut App, position: Vec3, size: Vec3) {
+        app.world_mut().spawn((
+            RigidBody::Static,
+            Collider::cuboid(size.x, size.y, size.z),
+            Transform::from_translation(position),
+            Position(position),
+            Rotation::default(),
+        ));
+    }
+
+    #[test]
+    fn obstacles_are_found_relative_to_grab_point() {
+        let mut app = physics_app();
+        // A wall whose front face is 2.5 m in front of the anchor.
+        spawn_static_box(&mut app, Vec3::new(0.0, 0.0, -3.0), Vec3::splat(1.0));
+        // Holding a 1 m cube at the center of its back face.
+        let grab_offset = Vec3::new(0.0, 0.0, 0.5);
+        let distance = app
+            .world_mut()
+            .run_system_once(move |mut spatial_query: SpatialQuery| {
+                spatial_query.update_pipeline();
+                cast_held_prop(
+                    &spatial_query,
+                    &Collider::cuboid(1.0, 1.0, 1.0),
+                    Vec3::ZERO,
+                    Quat::IDENTITY,
+                    grab_offset,
+                    Dir3::NEG_Z,
+                    &ShapeCastConfig::from_max_distance(10.0),
+                    &SpatialQueryFilter::default(),
+                )
+                .map(|hit| hit.distance)
+            })
+            .unwrap()
+            .expect("The wall should be hit");
+        // The whole cube is in front of the grab point.
+        assert!((distance - 1.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn prop_extent_is_measured_from_grab_point() {
+        let cube = Collider::cuboid(1.0, 1.0, 1.0);
+        let from_center = collide_get_extent(&cube, Vec3::ZERO, Quat::IDENTITY, Dir3::Z);
+        assert!((from_center - 0.5).abs() < 1e-3);
+        let from_grab_point =
+            collide_get_extent(&cube, Vec3::new(0.0, 0.0, 0.25), Quat::IDENTITY, Dir3::Z);
+        assert!((from_grab_point - 0.25).abs() < 1e-3);
+    }
+
+    #[test]
+    fn tilted_prop_is_aligned_to_surface() {
+        let tilted = Quat::from_rotation_x(0.3) * Quat::from_rotation_y(1.0);
+        let on_floor = align_up_to_normal(tilted, Vec3::Y);
+        assert!((on_floor * Vec3::Y).dot(Vec3::Y) > 0.999);
+        let on_wall = align_up_to_normal(tilted, Vec3::Z);
+        assert!((on_wall * Vec3::Y).dot(Vec3::Z) > 0.999);
+    }
+
+    #[test]
+    fn placement_is_offset_by_grab_point() {
+        // Held at the center of the cube's back face, turned to the side.
+        let rotation = Quat::from_rotation_y(FRAC_PI_2);
+        let grab_offset = Vec3::new(0.0, 0.0, 0.5);
+        let origin = Vec3::new(0.0, 2.0, 0.0) - rotation * grab_offset;
+        let translation = placed_translation(origin, Dir3::NEG_Y, 1.5, Vec3::Y);
+        assert!(translation.distance(Vec3::new(-0.5, 0.51, 0.0)) < 1e-4);
+    }
+
+    #[test]
+    fn rotations_snap_to_axes_within_threshold() {
+        assert_eq!(axis_aligned_rotations().count(), 24);
+        let threshold = 30.0_f32.to_radians();
+        let slightly_off = Quat::from_rotation_y(FRAC_PI_2 + 0.2);
+        let snapped = snap_to_axes(slightly_off, threshold);
+        assert!(snapped.angle_between(Quat::from_rotation_y(FRAC_PI_2)) < 1e-4);
+        let far_off = Quat::from_rotation_y(FRAC_PI_4);
+        assert_eq!(snap_to_axes(far_off, threshold), far_off);
+    }
+
     #[test]
     fn test_collide_get_extent() {
         let collider = Collider::capsule(0.3, 1.2);
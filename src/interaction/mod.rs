@@ -5,7 +5,11 @@ mod hold;
 mod pull;
 mod throw;
 
-pub(crate) use self::hold::prelude::*;
+pub use self::{hold::prelude::*, throw::prelude::*};
+pub(crate) use self::{
+    hold::prelude::{HoldManipulation, LastHold, PendingGrabPoint},
+    pull::pickup_prop,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((hold::plugin, pull::plugin, drop::plugin, throw::plugin));
@@ -6,6 +6,7 @@ pub(super) fn find_prop_in_cone(
     spatial_query: &SpatialQuery,
     origin: Transform,
     config: &AvianPickupActor,
+    predicate: &dyn Fn(Entity) -> bool,
     q_collider: &Query<&Position>,
 ) -> Option<Prop> {
     const MAGIC_OFFSET_ASK_VALVE: f32 = 1.0 * METERS_PER_INCH;
@@ -16,11 +17,18 @@ pub(super) fn find_prop_in_cone(
     let mut nearest_dist = config.hold.distance_to_allow_holding + MAGIC_OFFSET_ASK_VALVE;
     let box_collider = Cuboid::from_size(Vec3::splat(2.0 * nearest_dist)).into();
 
-    let colliders = spatial_query.shape_intersections(
+    let mut colliders = Vec::new();
+    spatial_query.shape_intersections_callback(
         &box_collider,
         origin.translation,
         origin.rotation,
         &config.prop_filter,
+        |entity| {
+            if predicate(entity) {
+                colliders.push(entity);
+            }
+            true
+        },
     );
     let mut canditate = None;
 
@@ -48,7 +56,7 @@ pub(super) fn find_prop_in_cone(
             dist,
             true,
             &config.obstacle_filter,
-            &|entity| q_collider.contains(entity),
+            &|entity| q_collider.contains(entity) && predicate(entity),
         ) {
             let occluded = hit.entity != collider && hit.distance <= dist;
             if occluded {
@@ -63,13 +71,14 @@ pub(super) fn find_prop_in_cone(
             dist,
             true,
             &config.prop_filter,
-            &|entity| q_collider.contains(entity),
+            &|entity| q_collider.contains(entity) && predicate(entity),
         ) {
             if hit.entity == collider {
                 nearest_dist = dist;
                 canditate.replace(Prop {
                     entity: collider,
                     toi: hit.distance,
+                    point: origin.translation + los * hit.distance,
                 });
             }
         }
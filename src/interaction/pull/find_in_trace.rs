@@ -6,25 +6,28 @@ pub(super) fn find_prop_in_trace(
     spatial_query: &SpatialQuery,
     origin: Transform,
     config: &AvianPickupActor,
+    predicate: &dyn Fn(Entity) -> bool,
 ) -> Option<Prop> {
     // Fun fact: Valve lies to you and actually multiplies this by 4 at this point.
     let test_length = config.interaction_distance;
     let shape_cast_config = ShapeCastConfig::from_max_distance(test_length);
-    let hit = spatial_query.cast_ray(
+    let hit = spatial_query.cast_ray_predicate(
         origin.translation,
         origin.forward(),
         test_length,
         true,
         &config.prop_filter,
+        predicate,
     );
 
     hit.filter(|hit| {
-        if let Some(terrain_hit) = spatial_query.cast_ray(
+        if let Some(terrain_hit) = spatial_query.cast_ray_predicate(
             origin.translation,
             origin.forward(),
             test_length,
             true,
             &config.obstacle_filter,
+            predicate,
         ) {
             let occluded = terrain_hit.entity != hit.entity
                 && terrain_hit.distance <= hit.distance;
@@ -38,6 +41,7 @@ pub(super) fn find_prop_in_trace(
         Prop {
             entity: hit.entity,
             toi: hit.distance,
+            point: origin.translation + origin.forward() * hit.distance,
         }
         .into()
     } else {
@@ -45,7 +49,7 @@ pub(super) fn find_prop_in_trace(
         const MAGIC_HALF_EXTENT_ASK_VALVE: f32 = 0.01;
         let fake_aabb_because_parry_cannot_do_aabb_casts =
             Cuboid::from_size(Vec3::splat(2. * MAGIC_HALF_EXTENT_ASK_VALVE)).into();
-        let hit = spatial_query.cast_shape(
+        let hit = spatial_query.cast_shape_predicate(
             &fake_aabb_because_parry_cannot_do_aabb_casts,
             origin.translation,
             origin.rotation,
@@ -54,9 +58,10 @@ pub(super) fn find_prop_in_trace(
             //test_length,
             //false,
             &config.prop_filter,
+            predicate,
         );
         hit.filter(|hit| {
-            if let Some(terrain_hit) = spatial_query.cast_shape(
+            if let Some(terrain_hit) = spatial_query.cast_shape_predicate(
                 &fake_aabb_because_parry_cannot_do_aabb_casts,
                 origin.translation,
                 origin.rotation,
@@ -65,6 +70,7 @@ pub(super) fn find_prop_in_trace(
                 //test_length,
                 //false,
                 &config.obstacle_filter,
+                predicate,
             ) {
                 let occluded = terrain_hit.entity != hit.entity
                     && terrain_hit.distance <= hit.distance;
@@ -76,6 +82,7 @@ pub(super) fn find_prop_in_trace(
         .map(|hit| Prop {
             entity: hit.entity,
             toi: hit.distance,
+            point: hit.point1,
         })
     }
 }
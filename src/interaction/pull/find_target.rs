@@ -0,0 +1,58 @@
+use super::Prop;
+use crate::prelude::*;
+
+/// Checks whether the given prop can be reached from the origin.
+/// Like `find_prop_in_trace`, but the ray is cast towards the prop instead of
+/// along the actor's forward direction.
+pub(super) fn find_target_prop(
+    spatial_query: &SpatialQuery,
+    origin: Transform,
+    config: &AvianPickupActor,
+    predicate: &dyn Fn(Entity) -> bool,
+    target: Entity,
+    q_collider: &Query<&Position>,
+    q_collider_parent: &Query<&ColliderParent>,
+) -> Option<Prop> {
+    let target_position = q_collider.get(target).ok()?.0;
+    let (direction, distance) = Dir3::new_and_length(target_position - origin.translation).ok()?;
+    let test_length = config.interaction_distance.min(distance);
+    let belongs_to_target = |entity: Entity| {
+        q_collider_parent
+            .get(entity)
+            .is_ok_and(|parent| parent.get() == target)
+    };
+
+    let hit = spatial_query.cast_ray_predicate(
+        origin.translation,
+        direction,
+        test_length,
+        true,
+        &config.prop_filter,
+        predicate,
+    )?;
+    if !belongs_to_target(hit.entity) {
+        // Another prop is in the way.
+        return None;
+    }
+
+    if let Some(terrain_hit) = spatial_query.cast_ray_predicate(
+        origin.translation,
+        direction,
+        test_length,
+        true,
+        &config.obstacle_filter,
+        predicate,
+    ) {
+        let occluded =
+            !belongs_to_target(terrain_hit.entity) && terrain_hit.distance <= hit.distance;
+        if occluded {
+            return None;
+        }
+    }
+
+    Some(Prop {
+        entity: hit.entity,
+        toi: hit.distance,
+        point: origin.translation + direction * hit.distance,
+    })
+}
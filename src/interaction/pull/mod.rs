@@ -1,14 +1,19 @@
+use avian3d::{math::Scalar, sync::ancestor_marker::AncestorMarker};
+
 use crate::{
-    math::GetBestGlobalTransform,
+    interaction::PendingGrabPoint,
+    math::{rigid_body_collider_entities, GetBestGlobalTransform},
     prelude::*,
-    verb::{Pulling, SetVerb, Verb},
+    slot::SlotOwner,
+    verb::{Holding, Pulling, SetVerb, Verb},
 };
 
 mod can_pull;
 mod find_in_cone;
 mod find_in_trace;
+mod find_target;
 
-use self::{can_pull::*, find_in_cone::*, find_in_trace::*};
+use self::{can_pull::*, find_in_cone::*, find_in_trace::*, find_target::*};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(PhysicsSchedule, find_object.in_set(HandleVerbSystem::Pull))
@@ -22,15 +27,13 @@ pub(super) fn plugin(app: &mut App) {
 fn find_object(
     mut commands: Commands,
     spatial_query: SpatialQuery,
-    mut q_actor: Query<
-        (
-            Entity,
-            &AvianPickupActor,
-            &mut AvianPickupActorState,
-            &mut Cooldown,
-        ),
-        With<Pulling>,
-    >,
+    mut q_actor: Query<(
+        Entity,
+        &AvianPickupActor,
+        &mut AvianPickupActorState,
+        &mut Cooldown,
+        &Pulling,
+    )>,
     q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
     q_collider_parent: Query<&ColliderParent>,
     mut q_rigid_body: Query<(
@@ -38,14 +41,66 @@ fn find_object(
         &Mass,
         &mut ExternalImpulse,
         &Position,
-        Has<HeldProp>,
+        &Rotation,
+        Option<&HeldProp>,
+        Option<&CooldownOverride>,
+        Option<&NonDynamicPropPolicyOverride>,
     )>,
     q_collider: Query<&Position>,
+    q_holder: Query<&AvianPickupActor>,
+    q_slot_owner: Query<&SlotOwner>,
+    q_slots: Query<&AvianPickupSlots>,
+    q_holding: Query<&Holding>,
+    q_collider_ancestor: Query<&Children, With<AncestorMarker<ColliderMarker>>>,
+    q_collider_shape: Query<(&Transform, &Collider, Option<&CollisionLayers>)>,
+    mut w_pull_started: EventWriter<PropPullStarted>,
+    mut w_pulled: EventWriter<PropPulled>,
+    mut w_pull_cancelled: EventWriter<PropPullCancelled>,
 ) {
-    for (actor, config, mut state, mut cooldown) in q_actor.iter_mut() {
-        let actor_transform = q_actor_transform.get_best_global_transform(actor);
-        let prop = find_prop_in_trace(&spatial_query, actor_transform, config)
-            .or_else(|| find_prop_in_cone(&spatial_query, actor_transform, config, &q_collider));
+    for (actor, config, mut state, mut cooldown, pulling) in q_actor.iter_mut() {
+        let actor_transform = q_actor_transform.get_aim_transform(actor, config);
+        // Slots of the same owner ignore the props held by the other slots and
+        // the colliders of the body they belong to, as these would otherwise
+        // usually block the view.
+        let excluded: Vec<Entity> =
+            match AvianPickupSlots::find_owner(actor, &q_slot_owner, &q_slots) {
+                Some((owner, slots)) => slots
+                    .0
+                    .iter()
+                    .filter(|&&slot| slot != actor)
+                    .filter_map(|&slot| q_holding.get(slot).ok())
+                    .map(|holding| holding.0)
+                    .chain(rigid_body_collider_entities(
+                        owner,
+                        &q_collider_ancestor,
+                        &q_collider_shape,
+                        &config.actor_filter,
+                    ))
+                    .collect(),
+                None => Vec::new(),
+            };
+        let predicate = |entity: Entity| !excluded.contains(&entity);
+        let prop = if let Some(target) = pulling.0 {
+            find_target_prop(
+                &spatial_query,
+                actor_transform,
+                config,
+                &predicate,
+                target,
+                &q_collider,
+                &q_collider_parent,
+            )
+        } else {
+            find_prop_in_trace(&spatial_query, actor_transform, config, &predicate).or_else(|| {
+                find_prop_in_cone(
+                    &spatial_query,
+                    actor_transform,
+                    config,
+                    &predicate,
+                    &q_collider,
+                )
+            })
+        };
 
         let Some(prop) = prop else {
             continue;
@@ -57,37 +112,208 @@ fn find_object(
         };
         let rigid_body_entity = rigid_body_entity.get();
 
-        let Ok((&rigid_body, &mass, mut impulse, prop_position, is_already_being_held)) =
-            q_rigid_body.get_mut(rigid_body_entity)
+        let Ok((
+            &rigid_body,
+            &mass,
+            mut impulse,
+            prop_position,
+            prop_rotation,
+            held_prop,
+            cooldown_override,
+            policy_override,
+        )) = q_rigid_body.get_mut(rigid_body_entity)
         else {
             // These components might not be present on non-dynamic rigid bodies
             continue;
         };
 
-        if is_already_being_held || !can_pull(rigid_body, mass, config) {
+        let policy = policy_override
+            .map(|p| p.0)
+            .unwrap_or(config.pull.non_dynamic_props);
+        let holder = held_prop
+            .map(|held| held.actor)
+            .filter(|&holder| holder != actor);
+        let holder_strength = holder.map(|holder| {
+            q_holder
+                .get(holder)
+                .map(|holder_config| holder_config.pull.strength)
+                .unwrap_or_default()
+        });
+        let can_take_from_holder =
+            can_take_from_holder(config.pull.contest, config.pull.strength, holder_strength);
+        if !can_take_from_holder || !can_pull(rigid_body, mass, config, policy) {
+            if *state == AvianPickupActorState::Pulling(rigid_body_entity) {
+                // Someone else grabbed the prop we were pulling.
+                *state = AvianPickupActorState::Idle;
+                let event = PropPullCancelled {
+                    actor,
+                    prop: rigid_body_entity,
+                };
+                w_pull_cancelled.send(event);
+                commands.trigger_targets(event, [rigid_body_entity, actor]);
+            }
             continue;
         }
 
         let can_hold = prop.toi <= config.interaction_distance;
+        if !can_hold && (!rigid_body.is_dynamic() || holder.is_some()) {
+            // Impulses have no effect on non-dynamic or held rigid bodies,
+            // so these can only be picked up when in reach.
+            continue;
+        }
+
+        if let AvianPickupActorState::Pulling(previous_prop) = *state {
+            if previous_prop != rigid_body_entity {
+                let event = PropPullCancelled {
+                    actor,
+                    prop: previous_prop,
+                };
+                w_pull_cancelled.send(event);
+                commands.trigger_targets(event, [previous_prop, actor]);
+            }
+        }
+
+        let cooldown_config = cooldown_override.map(|c| &c.0).unwrap_or(&config.cooldown);
         if can_hold {
-            cooldown.hold();
+            if let Some(holder) = holder {
+                commands.entity(holder).queue(SetVerb::new(Verb::Drop {
+                    prop: rigid_body_entity,
+                    reason: PropDropReason::Contested,
+                }));
+            }
+            cooldown.hold(cooldown_config);
+            if config.hold.grab_at_hit_point {
+                commands.entity(actor).insert(PendingGrabPoint {
+                    prop: rigid_body_entity,
+                    offset: prop_rotation.0.inverse() * (prop.point - prop_position.0),
+                });
+            }
             commands
                 .entity(actor)
                 .queue(SetVerb::new(Verb::Hold(rigid_body_entity)));
         } else {
-            let direction = (actor_transform.translation - prop_position.0).normalize_or_zero();
+            let anchor_transform = q_actor_transform.get_hold_anchor_transform(actor, config);
+            let direction = (anchor_transform.translation - prop_position.0).normalize_or_zero();
             let mass_adjustment = adjust_impulse_for_mass(mass);
             let pull_impulse = direction * config.pull.impulse * mass_adjustment;
-            cooldown.pull();
+            cooldown.pull(cooldown_config);
             impulse.apply_impulse(pull_impulse);
-            if !matches!(state.as_ref(), AvianPickupActorState::Pulling(..)) {
+            if *state != AvianPickupActorState::Pulling(rigid_body_entity) {
                 *state = AvianPickupActorState::Pulling(rigid_body_entity);
+                let event = PropPullStarted {
+                    actor,
+                    prop: rigid_body_entity,
+                };
+                w_pull_started.send(event);
+                commands.trigger_targets(event, [rigid_body_entity, actor]);
             }
+            let event = PropPulled {
+                actor,
+                prop: rigid_body_entity,
+                impulse: pull_impulse,
+            };
+            w_pulled.send(event);
+            commands.trigger_targets(event, [rigid_body_entity, actor]);
             commands.entity(actor).queue(SetVerb::new(None));
         }
     }
 }
 
+/// Makes an actor hold a prop directly, see
+/// [`AvianPickupCommandsExt::pickup`](crate::prelude::AvianPickupCommandsExt::pickup).
+/// Applies the same checks as [`find_object`], except for the distance.
+pub(crate) fn pickup_prop(
+    In((actor, prop)): In<(Entity, Entity)>,
+    mut commands: Commands,
+    mut q_actor: Query<(
+        &AvianPickupActor,
+        &mut AvianPickupActorState,
+        &mut Cooldown,
+        Option<&Holding>,
+    )>,
+    q_prop: Query<(
+        &RigidBody,
+        &Mass,
+        Option<&HeldProp>,
+        Option<&CooldownOverride>,
+        Option<&NonDynamicPropPolicyOverride>,
+    )>,
+    q_holder: Query<&AvianPickupActor>,
+    mut w_pull_cancelled: EventWriter<PropPullCancelled>,
+) {
+    let Ok((config, mut state, mut cooldown, holding)) = q_actor.get_mut(actor) else {
+        error!("Actor entity was deleted or in an invalid state. Ignoring.");
+        return;
+    };
+    let held = holding.map(|holding| holding.0);
+    if held == Some(prop) {
+        return;
+    }
+    let Ok((&rigid_body, &mass, held_prop, cooldown_override, policy_override)) = q_prop.get(prop)
+    else {
+        // These components might not be present on non-dynamic rigid bodies
+        return;
+    };
+
+    let policy = policy_override
+        .map(|p| p.0)
+        .unwrap_or(config.pull.non_dynamic_props);
+    let holder = held_prop
+        .map(|held| held.actor)
+        .filter(|&holder| holder != actor);
+    let holder_strength = holder.map(|holder| {
+        q_holder
+            .get(holder)
+            .map(|holder_config| holder_config.pull.strength)
+            .unwrap_or_default()
+    });
+    if !can_take_from_holder(config.pull.contest, config.pull.strength, holder_strength)
+        || !can_pull(rigid_body, mass, config, policy)
+    {
+        return;
+    }
+
+    if let AvianPickupActorState::Pulling(previous_prop) = *state {
+        if previous_prop != prop {
+            *state = AvianPickupActorState::Idle;
+            let event = PropPullCancelled {
+                actor,
+                prop: previous_prop,
+            };
+            w_pull_cancelled.send(event);
+            commands.trigger_targets(event, [previous_prop, actor]);
+        }
+    }
+    if let Some(held) = held {
+        commands.entity(actor).queue(SetVerb::new(Verb::Drop {
+            prop: held,
+            reason: PropDropReason::Scripted,
+        }));
+    }
+    if let Some(holder) = holder {
+        commands.entity(holder).queue(SetVerb::new(Verb::Drop {
+            prop,
+            reason: PropDropReason::Contested,
+        }));
+    }
+    let cooldown_config = cooldown_override.map(|c| &c.0).unwrap_or(&config.cooldown);
+    cooldown.hold(cooldown_config);
+    commands.entity(actor).queue(SetVerb::new(Verb::Hold(prop)));
+}
+
+/// Whether an actor can take a prop from the actor currently holding it.
+/// `holder_strength` is `None` if no other actor is holding the prop.
+fn can_take_from_holder(
+    contest: PropContestPolicy,
+    strength: Scalar,
+    holder_strength: Option<Scalar>,
+) -> bool {
+    match holder_strength {
+        Some(holder_strength) => contest.allows(strength, holder_strength),
+        None => true,
+    }
+}
+
 /// Taken from [this snippet](https://github.com/ValveSoftware/source-sdk-2013/blob/master/sp/src/game/server/hl2/weapon_physcannon.cpp#L2607-L2610)
 fn adjust_impulse_for_mass(mass: Mass) -> f32 {
     if mass.0 < 50.0 {
@@ -102,10 +328,16 @@ fn adjust_impulse_for_mass(mass: Mass) -> f32 {
 struct Prop {
     pub entity: Entity,
     pub toi: f32,
+    /// Where the prop was hit in world space
+    pub point: Vec3,
 }
 
-fn flush_pulling_state(mut q_state: Query<(Mut<AvianPickupActorState>, Has<Pulling>, &Cooldown)>) {
-    for (mut state, has_pulling, cooldown) in q_state.iter_mut() {
+fn flush_pulling_state(
+    mut commands: Commands,
+    mut q_state: Query<(Entity, Mut<AvianPickupActorState>, Has<Pulling>, &Cooldown)>,
+    mut w_pull_cancelled: EventWriter<PropPullCancelled>,
+) {
+    for (actor, mut state, has_pulling, cooldown) in q_state.iter_mut() {
         // Okay, so the basic idea is this:
         // Pulling happens in discrete impulses every n milliseconds.
         // New pulls happen regularly, but we should also reset to idle at some point.
@@ -117,11 +349,56 @@ fn flush_pulling_state(mut q_state: Query<(Mut<AvianPickupActorState>, Has<Pulli
         //
         // The reason we check for `!has_pulling` is that a missing `Pulling` means
         // that no input was given to start / continue pulling during `Update`.
-        if matches!(state.as_ref(), AvianPickupActorState::Pulling(..))
-            && !has_pulling
-            && cooldown.finished(AvianPickupAction::Pull)
-        {
+        let AvianPickupActorState::Pulling(prop) = *state else {
+            continue;
+        };
+        if !has_pulling && cooldown.finished(AvianPickupAction::Pull) {
             *state = AvianPickupActorState::Idle;
+            let event = PropPullCancelled { actor, prop };
+            w_pull_cancelled.send(event);
+            commands.trigger_targets(event, [prop, actor]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn props_without_holder_can_always_be_taken() {
+        for contest in [
+            PropContestPolicy::Never,
+            PropContestPolicy::StrongerActor,
+            PropContestPolicy::Always,
+        ] {
+            assert!(can_take_from_holder(contest, 1.0, None));
         }
     }
+
+    #[test]
+    fn never_contest_keeps_props_with_their_holder() {
+        assert!(!can_take_from_holder(
+            PropContestPolicy::Never,
+            10.0,
+            Some(1.0)
+        ));
+    }
+
+    #[test]
+    fn stronger_actor_contest_compares_strength() {
+        let contest = PropContestPolicy::StrongerActor;
+        assert!(can_take_from_holder(contest, 2.0, Some(1.0)));
+        assert!(!can_take_from_holder(contest, 1.0, Some(1.0)));
+        assert!(!can_take_from_holder(contest, 1.0, Some(2.0)));
+    }
+
+    #[test]
+    fn always_contest_takes_props_from_anyone() {
+        assert!(can_take_from_holder(
+            PropContestPolicy::Always,
+            1.0,
+            Some(2.0)
+        ));
+    }
 }
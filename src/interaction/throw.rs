@@ -1,12 +1,77 @@
 use std::ops::RangeInclusive;
 
 use avian3d::math::Scalar;
-use rand::Rng;
+use rand::{Rng, RngCore};
 
-use crate::{math::GetBestGlobalTransform, prelude::*, rng::RngSource, verb::Throwing};
+use crate::{
+    actor::actor_velocity,
+    interaction::LastHold,
+    math::GetBestGlobalTransform,
+    prelude::*,
+    rng::{AvianPickupRng, RngSource},
+    slot::SlotOwner,
+    verb::{SetVerb, ThrowAim, Throwing, Verb},
+};
+
+pub(super) mod prelude {
+    pub use super::ThrowCharge;
+}
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(PhysicsSchedule, throw.in_set(HandleVerbSystem::Throw).ambiguous_with(PhysicsStepSet::First));
+    app.add_systems(
+        PhysicsSchedule,
+        throw
+            .in_set(HandleVerbSystem::Throw)
+            .ambiguous_with(PhysicsStepSet::First),
+    )
+    .add_systems(
+        PhysicsSchedule,
+        tick_throw_charge.in_set(AvianPickupSystem::TickTimers),
+    );
+}
+
+/// The charge of a throw started with
+/// [`AvianPickupAction::BeginThrowCharge`].
+/// This component is automatically added to the entity holding the
+/// [`AvianPickupActor`] while charging and removed once the prop is thrown or
+/// no longer held.\
+/// Read this to e.g. show a charge bar in the HUD.
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct ThrowCharge {
+    /// Time in seconds since the charge started
+    pub(crate) elapsed: Scalar,
+    /// Time in seconds until the charge is full
+    pub(crate) duration: Scalar,
+}
+
+impl ThrowCharge {
+    pub(crate) fn new(duration: Scalar) -> Self {
+        Self {
+            elapsed: 0.0,
+            duration,
+        }
+    }
+
+    /// The time in seconds since the charge started.
+    pub fn elapsed(&self) -> Scalar {
+        self.elapsed
+    }
+
+    /// How far the charge has progressed, between 0.0 (just started) and 1.0
+    /// (fully charged).
+    pub fn fraction(&self) -> Scalar {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        (self.elapsed / self.duration).min(1.0)
+    }
+}
+
+fn tick_throw_charge(mut q_charge: Query<&mut ThrowCharge>, time: Res<Time>) {
+    let dt = time.delta_secs() as Scalar;
+    for mut charge in q_charge.iter_mut() {
+        charge.elapsed += dt;
+    }
 }
 
 /// Note: in constrast to the physcannon, we do not allow punting when not
@@ -19,54 +84,250 @@ fn throw(
         &mut AvianPickupActorState,
         &mut Cooldown,
         &Throwing,
+        Option<&LastHold>,
     )>,
     q_actor_transform: Query<(&GlobalTransform, Option<&Position>, Option<&Rotation>)>,
+    mut q_velocity: Query<&mut LinearVelocity>,
     mut q_prop: Query<(
-        &mut LinearVelocity,
         &mut AngularVelocity,
+        &Position,
+        &Rotation,
+        Option<&GravityScale>,
         &Mass,
+        Option<&NonPickupMass>,
         Option<&ThrownLinearSpeedOverride>,
         Option<&ThrownAngularSpeedOverride>,
+        Option<&ThrownSpin>,
+        Option<&CooldownOverride>,
     )>,
+    q_velocity_source: Query<&AvianPickupActorVelocity>,
+    q_parent: Query<&Parent>,
+    q_slot_owner: Query<&SlotOwner>,
+    q_slots: Query<&AvianPickupSlots>,
     mut w_throw_event: EventWriter<PropThrown>,
-    mut rng: ResMut<RngSource>,
+    mut q_rng: Query<&mut AvianPickupRng>,
+    mut rng_source: ResMut<RngSource>,
+    gravity: Res<Gravity>,
 ) {
-    for (actor, config, mut states, mut cooldown, throw) in q_actor.iter_mut() {
-        let prop = throw.0;
-        commands.entity(actor).remove::<Throwing>();
-        let actor_transform = q_actor_transform.get_best_global_transform(actor);
+    for (actor, config, mut states, mut cooldown, throw, last_hold) in q_actor.iter_mut() {
+        let prop = throw.prop;
+        commands.entity(actor).remove::<(Throwing, LastHold)>();
+        let actor_transform = q_actor_transform.get_aim_transform(actor, config);
+        // Resolve the target before borrowing the prop's velocity mutably,
+        // as the target might be a rigid body itself.
+        let target = match throw.aim {
+            ThrowAim::Target(ThrowTarget::Point(point)) => Some((point, Vec3::ZERO)),
+            ThrowAim::Target(ThrowTarget::Entity(entity)) => q_actor_transform
+                .try_get_best_global_transform(entity)
+                .map(|transform| {
+                    let target_velocity = q_velocity.get(entity).map(|v| v.0).unwrap_or_default();
+                    (transform.translation, target_velocity)
+                }),
+            ThrowAim::Forward | ThrowAim::Velocity(_) => None,
+        };
+        let inherited_velocity = actor_velocity(
+            actor,
+            |entity| q_velocity.get(entity).ok().map(|v| v.0),
+            &q_velocity_source,
+            &q_parent,
+            &q_slot_owner,
+            &q_slots,
+        ) * config.inherited_velocity;
         // Safety: All props are rigid bodies, which are guaranteed to have a
         // `LinearVelocity`, `AngularVelocity`, and `Mass`.
-        let Ok((mut velocity, mut angvel, mass, lin_speed_override, ang_speed_override)) =
-            q_prop.get_mut(prop)
+        let (
+            Ok(mut velocity),
+            Ok((
+                mut angvel,
+                position,
+                rotation,
+                gravity_scale,
+                mass,
+                non_pickup_mass,
+                lin_speed_override,
+                ang_speed_override,
+                thrown_spin,
+                cooldown_override,
+            )),
+        ) = (q_velocity.get_mut(prop), q_prop.get_mut(prop))
         else {
-            error!("Prop entity was deleted or in an invalid state. Ignoring.");
+            commands.entity(actor).queue(SetVerb::new(Verb::Drop {
+                prop,
+                reason: PropDropReason::Lost,
+            }));
             continue;
         };
         // The 2013 code now does a `continue` on
         // `prop_dist_sq > config.interaction_distance * config.interaction_distance`
         // but eh, that's fine. Better to respect players' input in such edge cases.
 
-        let lin_direction = actor_transform.forward();
         let lin_speed = lin_speed_override
             .map(|s| s.0)
             .unwrap_or_else(|| calculate_launch_speed(config, *mass));
-        velocity.0 = lin_direction * lin_speed;
+        let lin_speed = throw
+            .charge
+            .map(|charge| calculate_charged_speed(config, lin_speed, charge))
+            .unwrap_or(lin_speed);
+        velocity.0 = match (throw.aim, target) {
+            (ThrowAim::Velocity(velocity), _) => velocity,
+            (_, Some((target_position, target_velocity))) => {
+                let gravity = gravity.0 * gravity_scale.map(|s| s.0).unwrap_or(1.0);
+                // Solve in the frame of the inherited velocity so that the
+                // prop still hits the target.
+                let velocity = solve_ballistic_arc(
+                    target_position - position.0,
+                    target_velocity - inherited_velocity,
+                    lin_speed,
+                    gravity,
+                    config.throw.arc,
+                );
+                velocity + inherited_velocity
+            }
+            _ => actor_transform.forward() * lin_speed + inherited_velocity,
+        };
 
-        let rand_direction = random_unit_vector(rng.as_mut());
-        let rand_magnitude = ang_speed_override.map(|s| s.0).unwrap_or_else(|| {
-            rng.as_mut()
-                .gen_range(config.throw.angular_speed_range.clone())
-        });
-        angvel.0 = rand_direction * rand_magnitude;
+        let mut actor_rng = q_rng.get_mut(actor).ok();
+        let rng: &mut dyn RngCore = match actor_rng.as_mut() {
+            Some(actor_rng) => &mut actor_rng.0,
+            None => rng_source.as_mut(),
+        };
+        let (spin_axis, spin_range) = thrown_spin
+            .map(|spin| (spin.axis, &spin.angular_speed_range))
+            .unwrap_or((config.throw.spin_axis, &config.throw.angular_speed_range));
+        angvel.0 = calculate_spin(
+            rng,
+            spin_axis,
+            spin_range,
+            ang_speed_override.map(|s| s.0),
+            actor_transform.rotation,
+            rotation.0,
+        );
 
         *states = AvianPickupActorState::Idle;
-        w_throw_event.send(PropThrown { actor, prop });
-        cooldown.throw();
+        // The prop's mass was already restored when it stopped being held.
+        let mass = non_pickup_mass.map(|m| m.0).unwrap_or(mass.0);
+        let last_hold = last_hold.filter(|last_hold| last_hold.prop == prop);
+        let event = PropThrown {
+            actor,
+            prop,
+            position: position.0,
+            linear_velocity: velocity.0,
+            angular_velocity: angvel.0,
+            speed: velocity.length(),
+            mass,
+            temporary_mass: last_hold.map(|l| l.temporary_mass).unwrap_or(mass),
+            hold_duration: last_hold.map(|l| l.duration).unwrap_or_default(),
+            charge: throw.charge,
+        };
+        w_throw_event.send(event);
+        commands.trigger_targets(event, [prop, actor]);
+        cooldown.throw(cooldown_override.map(|c| &c.0).unwrap_or(&config.cooldown));
     }
 }
 
-fn random_unit_vector(rng: &mut impl Rng) -> Vec3 {
+/// Returns the launch velocity with the given speed that makes a projectile
+/// starting at the origin hit a target at `displacement` that moves with
+/// `target_velocity`. If the target cannot be reached, the returned velocity
+/// throws the projectile as far as possible towards it instead.
+fn solve_ballistic_arc(
+    displacement: Vec3,
+    target_velocity: Vec3,
+    speed: Scalar,
+    gravity: Vec3,
+    arc: ThrowArc,
+) -> Vec3 {
+    // A couple of iterations converge nicely for targets slower than the throw.
+    const LEAD_ITERATIONS: usize = 4;
+    let mut aim_point = displacement;
+    let mut launch_velocity = Vec3::ZERO;
+    for _ in 0..LEAD_ITERATIONS {
+        let (velocity, time) = solve_ballistic_arc_to_point(aim_point, speed, gravity, arc);
+        launch_velocity = velocity;
+        aim_point = displacement + target_velocity * time;
+    }
+    launch_velocity
+}
+
+/// Returns the launch velocity and the time of flight needed to hit a static
+/// target at `displacement`.
+///
+/// The projectile's position is `v * t + g * t² / 2`, which must equal `d`.
+/// Requiring `|v| = speed` results in a quadratic equation in `t²`:
+/// `|g|² / 4 * t⁴ - (d · g + speed²) * t² + |d|² = 0`.
+/// Its two solutions are the low and the high arc.
+fn solve_ballistic_arc_to_point(
+    displacement: Vec3,
+    speed: Scalar,
+    gravity: Vec3,
+    arc: ThrowArc,
+) -> (Vec3, Scalar) {
+    let straight_throw = || {
+        let time = if speed > 0.0 {
+            displacement.length() / speed
+        } else {
+            0.0
+        };
+        (displacement.normalize_or_zero() * speed, time)
+    };
+    let a = gravity.length_squared() / 4.0;
+    if a < f32::EPSILON {
+        return straight_throw();
+    }
+    let b = -(displacement.dot(gravity) + speed * speed);
+    let c = displacement.length_squared();
+    let discriminant = b * b - 4.0 * a * c;
+    let reachable = discriminant >= 0.0;
+    let time_squared = if reachable {
+        let sqrt_discriminant = discriminant.sqrt();
+        match arc {
+            ThrowArc::Low => (-b - sqrt_discriminant) / (2.0 * a),
+            ThrowArc::High => (-b + sqrt_discriminant) / (2.0 * a),
+        }
+    } else {
+        // Aim as if we could just barely reach the target.
+        -b / (2.0 * a)
+    };
+    if time_squared <= f32::EPSILON {
+        return straight_throw();
+    }
+    let time = time_squared.sqrt();
+    let velocity = displacement / time - gravity * time / 2.0;
+    let velocity = if reachable {
+        velocity
+    } else {
+        velocity.normalize_or_zero() * speed
+    };
+    (velocity, time)
+}
+
+/// Returns the angular velocity of a thrown prop.
+/// Only draws from `rng` for the parts of the spin that are actually random,
+/// so that throws without random spin don't change the outcome of later throws.
+fn calculate_spin(
+    rng: &mut dyn RngCore,
+    axis: SpinAxis,
+    speed_range: &RangeInclusive<Scalar>,
+    speed_override: Option<Scalar>,
+    actor_rotation: Quat,
+    prop_rotation: Quat,
+) -> Vec3 {
+    let speed = match speed_override {
+        Some(speed) => speed,
+        None if speed_range.start() == speed_range.end() => *speed_range.start(),
+        None => rng.gen_range(speed_range.clone()),
+    };
+    if speed == 0.0 {
+        return Vec3::ZERO;
+    }
+    let axis = match axis {
+        SpinAxis::Random => random_unit_vector(rng),
+        SpinAxis::Actor(axis) => (actor_rotation * axis).normalize_or_zero(),
+        SpinAxis::Prop(axis) => (prop_rotation * axis).normalize_or_zero(),
+    };
+    axis * speed
+}
+
+fn random_unit_vector(rng: &mut (impl Rng + ?Sized)) -> Vec3 {
     Sphere::new(1.0).sample_boundary(rng)
 }
 
@@ -85,6 +346,17 @@ fn calculate_launch_speed(config: &AvianPickupActor, mass: Mass) -> Scalar {
     }
 }
 
+/// Interpolates between the minimum speed and the speed the prop would have
+/// been thrown with without charging.
+fn calculate_charged_speed(
+    config: &AvianPickupActor,
+    full_speed: Scalar,
+    charge: Scalar,
+) -> Scalar {
+    let min_speed = config.throw.linear_speed_range.start().min(full_speed);
+    min_speed + (full_speed - min_speed) * config.throw.charge_curve.sample(charge)
+}
+
 /// Remaps a value `val` in range `domain` from linear
 /// to spline using `simple_spline`, giving an output in `image`.
 /// `domain` and `image` are mathematical terms.
@@ -134,6 +406,67 @@ mod test {
         assert_eq!(remap(1000.), MINFORCE);
     }
 
+    #[test]
+    fn ballistic_arcs_hit_target() {
+        let gravity = Vec3::new(0.0, -9.81, 0.0);
+        let displacement = Vec3::new(8.0, 1.0, -3.0);
+        let speed = 12.0;
+        for arc in [ThrowArc::Low, ThrowArc::High] {
+            let (velocity, time) = solve_ballistic_arc_to_point(displacement, speed, gravity, arc);
+            assert!((velocity.length() - speed).abs() < 1e-3);
+            let landing = velocity * time + gravity * time * time / 2.0;
+            assert!(landing.distance(displacement) < 1e-3);
+        }
+
+        let target_velocity = Vec3::new(0.0, 0.0, 2.0);
+        let velocity =
+            solve_ballistic_arc(displacement, target_velocity, speed, gravity, ThrowArc::Low);
+        let closest_approach = (0..3000)
+            .map(|i| i as f32 * 1e-3)
+            .map(|t| {
+                let projectile = velocity * t + gravity * t * t / 2.0;
+                let target = displacement + target_velocity * t;
+                projectile.distance(target)
+            })
+            .fold(f32::INFINITY, f32::min);
+        assert!(closest_approach < 0.05);
+    }
+
+    fn spin(rng: &mut AvianPickupRng, axis: SpinAxis, speed_range: RangeInclusive<Scalar>) -> Vec3 {
+        calculate_spin(
+            &mut rng.0,
+            axis,
+            &speed_range,
+            None,
+            Quat::IDENTITY,
+            Quat::IDENTITY,
+        )
+    }
+
+    #[test]
+    fn same_seed_spins_the_same() {
+        let mut first = AvianPickupRng::from_seed(42);
+        let mut second = AvianPickupRng::from_seed(42);
+        for _ in 0..2 {
+            let first_spin = spin(&mut first, SpinAxis::Random, 0.0..=1.0);
+            let second_spin = spin(&mut second, SpinAxis::Random, 0.0..=1.0);
+            assert_ne!(first_spin, Vec3::ZERO);
+            assert_eq!(first_spin, second_spin);
+        }
+    }
+
+    #[test]
+    fn fixed_spin_does_not_draw_from_rng() {
+        let mut rng = AvianPickupRng::from_seed(42);
+        let mut untouched = rng.clone();
+        assert_eq!(spin(&mut rng, SpinAxis::Random, 0.0..=0.0), Vec3::ZERO);
+        assert_eq!(
+            spin(&mut rng, SpinAxis::Actor(Vec3::X), 2.0..=2.0),
+            Vec3::X * 2.0
+        );
+        assert_eq!(rng.0.next_u64(), untouched.0.next_u64());
+    }
+
     #[test]
     fn is_random_unit_vector_actually_unit() {
         let mut rng = thread_rng();
@@ -6,13 +6,15 @@ use avian3d::prelude::*;
 use bevy::prelude::*;
 
 pub mod actor;
-mod cooldown;
+pub mod commands;
+pub mod cooldown;
 pub mod input;
 mod interaction;
 mod math;
 pub mod output;
 pub mod prop;
 mod rng;
+pub mod slot;
 mod verb;
 
 pub use rng::*;
@@ -22,13 +24,12 @@ pub mod prelude {
     pub(crate) use bevy::prelude::*;
 
     pub(crate) use super::{
-        cooldown::prelude::*,
-        prop::{prelude::*, NonPickupMass},
+        prop::{prelude::*, NonPickupMass, NonPickupRigidBody},
         HandleVerbSystem,
     };
     pub use crate::{
-        actor::prelude::*, input::prelude::*, output::prelude::*, AvianPickupPlugin,
-        AvianPickupSystem,
+        actor::prelude::*, commands::prelude::*, cooldown::prelude::*, input::prelude::*,
+        output::prelude::*, slot::prelude::*, AvianPickupPlugin, AvianPickupRng, AvianPickupSystem,
     };
 }
 
@@ -96,6 +97,7 @@ impl Plugin for AvianPickupPlugin {
             prop::plugin,
             verb::plugin,
             rng::plugin,
+            slot::plugin,
         ));
     }
 }
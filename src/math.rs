@@ -1,6 +1,8 @@
 use avian3d::{prelude::*, sync::ancestor_marker::AncestorMarker};
 use bevy::prelude::*;
 
+use crate::prelude::AvianPickupActor;
+
 pub(crate) const METERS_PER_INCH: f32 = 0.0254;
 
 pub(crate) fn rigid_body_compound_collider(
@@ -10,10 +12,52 @@ pub(crate) fn rigid_body_compound_collider(
     filter: &SpatialQueryFilter,
 ) -> Option<Collider> {
     let mut colliders = Vec::new();
+    collect_rigid_body_colliders(
+        rigid_body,
+        q_collider_ancestor,
+        q_collider,
+        filter,
+        &mut |_entity, transform: Transform, collider: &Collider| {
+            colliders.push((transform.translation, transform.rotation, collider.clone()));
+        },
+    );
+    if colliders.is_empty() {
+        None
+    } else {
+        Some(Collider::compound(colliders))
+    }
+}
+
+/// Returns the entities of all colliders in the hierarchy of `rigid_body`
+/// that pass the `filter`.
+pub(crate) fn rigid_body_collider_entities(
+    rigid_body: Entity,
+    q_collider_ancestor: &Query<&Children, With<AncestorMarker<ColliderMarker>>>,
+    q_collider: &Query<(&Transform, &Collider, Option<&CollisionLayers>)>,
+    filter: &SpatialQueryFilter,
+) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    collect_rigid_body_colliders(
+        rigid_body,
+        q_collider_ancestor,
+        q_collider,
+        filter,
+        &mut |entity, _transform, _collider: &Collider| entities.push(entity),
+    );
+    entities
+}
+
+fn collect_rigid_body_colliders(
+    rigid_body: Entity,
+    q_collider_ancestor: &Query<&Children, With<AncestorMarker<ColliderMarker>>>,
+    q_collider: &Query<(&Transform, &Collider, Option<&CollisionLayers>)>,
+    filter: &SpatialQueryFilter,
+    on_collider: &mut impl FnMut(Entity, Transform, &Collider),
+) {
     if let Ok((&_transform, col, layers)) = q_collider.get(rigid_body) {
         let layers = layers.copied().unwrap_or_default();
         if filter.test(rigid_body, layers) {
-            colliders.push((Vec3::ZERO, Quat::IDENTITY, col.clone()));
+            on_collider(rigid_body, Transform::IDENTITY, col);
         }
     }
     if let Ok(children) = q_collider_ancestor.get(rigid_body) {
@@ -23,15 +67,10 @@ pub(crate) fn rigid_body_compound_collider(
                 q_collider_ancestor,
                 q_collider,
                 filter,
-                &mut colliders,
+                on_collider,
             );
         }
     }
-    if colliders.is_empty() {
-        None
-    } else {
-        Some(Collider::compound(colliders))
-    }
 }
 
 fn rigid_body_compound_collider_recursive(
@@ -39,12 +78,12 @@ fn rigid_body_compound_collider_recursive(
     q_collider_ancestor: &Query<&Children, With<AncestorMarker<ColliderMarker>>>,
     q_collider: &Query<(&Transform, &Collider, Option<&CollisionLayers>)>,
     filter: &SpatialQueryFilter,
-    colliders: &mut Vec<(Vec3, Quat, Collider)>,
+    on_collider: &mut impl FnMut(Entity, Transform, &Collider),
 ) {
     if let Ok((&transform, collider, layers)) = q_collider.get(candidate) {
         let layers = layers.copied().unwrap_or_default();
         if filter.test(candidate, layers) {
-            colliders.push((transform.translation, transform.rotation, collider.clone()));
+            on_collider(candidate, transform, collider);
         }
     }
     if let Ok(children) = q_collider_ancestor.get(candidate) {
@@ -54,28 +93,45 @@ fn rigid_body_compound_collider_recursive(
                 q_collider_ancestor,
                 q_collider,
                 filter,
-                colliders,
+                on_collider,
             );
         }
     }
 }
 
 pub(crate) trait GetBestGlobalTransform {
-    fn get_best_global_transform(&self, entity: Entity) -> Transform;
+    fn try_get_best_global_transform(&self, entity: Entity) -> Option<Transform>;
+
+    fn get_best_global_transform(&self, entity: Entity) -> Transform {
+        self.try_get_best_global_transform(entity)
+            .expect("Got an entity without `GlobalTransform`")
+    }
+
+    /// The transform used for looking for props and orienting them.
+    /// See [`AvianPickupActor::aim_source`].
+    fn get_aim_transform(&self, actor: Entity, config: &AvianPickupActor) -> Transform {
+        self.try_get_best_global_transform(config.aim_entity(actor))
+            .unwrap_or_else(|| self.get_best_global_transform(actor))
+    }
+
+    /// The transform used as the origin for holding props.
+    /// See [`AvianPickupActor::hold_anchor`].
+    fn get_hold_anchor_transform(&self, actor: Entity, config: &AvianPickupActor) -> Transform {
+        self.try_get_best_global_transform(config.hold_anchor_entity(actor))
+            .unwrap_or_else(|| self.get_best_global_transform(actor))
+    }
 }
 
 impl GetBestGlobalTransform
     for Query<'_, '_, (&GlobalTransform, Option<&Position>, Option<&Rotation>)>
 {
-    fn get_best_global_transform(&self, entity: Entity) -> Transform {
-        let (global_transform, position, rotation) = self
-            .get(entity)
-            .expect("Got an entity without `GlobalTransform`");
+    fn try_get_best_global_transform(&self, entity: Entity) -> Option<Transform> {
+        let (global_transform, position, rotation) = self.get(entity).ok()?;
         if let Some(position) = position {
             if let Some(rotation) = rotation {
-                return Transform::from_translation(position.0).with_rotation(rotation.0);
+                return Some(Transform::from_translation(position.0).with_rotation(rotation.0));
             }
         }
-        global_transform.compute_transform()
+        Some(global_transform.compute_transform())
     }
 }
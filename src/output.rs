@@ -1,24 +1,66 @@
-//! Events related to props being thrown and dropped.
+//! Events related to props being pulled, picked up, thrown and dropped.
 //! These will be sent by the Avian Pickup plugin to notify the user of
 //! prop-related events. Handle these to e.g. play sound effects or show
 //! visual effects.
+//!
+//! Lifecycle events are additionally triggered as observer events targeting
+//! both the prop and the actor, so you can react to them for a specific
+//! entity by using [`EntityCommands::observe`]. Note that this means that
+//! global observers will run once for the prop and once for the actor.
+
+use std::time::Duration;
+
+use avian3d::math::Scalar;
 
 use crate::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<(PropThrown, PropDropped)>()
-        .add_event::<PropThrown>()
-        .add_event::<PropDropped>();
+    app.register_type::<(
+        PropThrown,
+        PropDropped,
+        PropPullStarted,
+        PropPulled,
+        PropPullCancelled,
+        PropPickedUp,
+        PropHoldLost,
+    )>()
+    .add_event::<PropThrown>()
+    .add_event::<PropDropped>()
+    .add_event::<PropPullStarted>()
+    .add_event::<PropPulled>()
+    .add_event::<PropPullCancelled>()
+    .add_event::<PropPickedUp>()
+    .add_event::<PropHoldLost>();
 }
 
 pub(super) mod prelude {
-    pub use super::{PropDropped, PropThrown};
+    pub use super::{
+        HoldLostReason, PropDropReason, PropDropped, PropHoldLost, PropPickedUp, PropPullCancelled,
+        PropPullStarted, PropPulled, PropThrown,
+    };
 }
 
 /// Event sent when a prop is thrown by an actor.
 /// This is meant for the user to lister to in order to play sound effects, etc.
 /// Sending this has no effect on the prop itself.
-#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+///
+/// Also triggered as an observer event on both the prop and the actor.
+///
+/// # Example
+///
+/// ```
+/// # use avian3d::prelude::*;
+/// # use avian_pickup::prelude::*;
+/// # use bevy::prelude::*;
+/// fn spawn_grenade(mut commands: Commands) {
+///     commands
+///         .spawn((RigidBody::Dynamic, Collider::sphere(0.1)))
+///         .observe(|trigger: Trigger<PropThrown>| {
+///             info!("Grenade thrown by {}, arming it now", trigger.event().actor);
+///         });
+/// }
+/// ```
+#[derive(Event, Debug, Clone, Copy, PartialEq, Reflect)]
 #[reflect(Debug, PartialEq)]
 #[cfg_attr(
     feature = "serialize",
@@ -30,12 +72,35 @@ pub struct PropThrown {
     pub prop: Entity,
     /// The actor that threw the prop.
     pub actor: Entity,
+    /// The world position of the prop when it was released.
+    pub position: Vec3,
+    /// The linear velocity in m/s the prop was thrown with.
+    pub linear_velocity: Vec3,
+    /// The angular velocity in rad/s the prop was thrown with.
+    pub angular_velocity: Vec3,
+    /// The linear speed in m/s the prop was thrown with.
+    pub speed: Scalar,
+    /// The mass in kg of the prop, i.e. the one it has when not being held.
+    pub mass: Scalar,
+    /// The mass in kg the prop had while being held, see
+    /// [`AvianPickupActorHoldConfig::temporary_prop_mass`].
+    pub temporary_mass: Scalar,
+    /// How long the prop was held before being thrown.
+    pub hold_duration: Duration,
+    /// The charge fraction between 0.0 and 1.0 if this was a charged throw,
+    /// see [`ThrowCharge`].
+    pub charge: Option<Scalar>,
 }
 
 /// Event sent when a prop is dropped by an actor.
 /// This is meant for the user to lister to in order to play sound effects, etc.
 /// Sending this has no effect on the prop itself.
-#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+///
+/// Also triggered as an observer event on both the prop and the actor.
+///
+/// If the prop was despawned, i.e. the reason is [`PropDropReason::Lost`],
+/// the fields describing its physical state are zero.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Reflect)]
 #[reflect(Debug, PartialEq)]
 #[cfg_attr(
     feature = "serialize",
@@ -47,8 +112,185 @@ pub struct PropDropped {
     pub prop: Entity,
     /// The actor that dropped the prop.
     pub actor: Entity,
-    /// Whether the drop was forced to be dropped by being too far away from its
-    /// target location. If `false`, the prop was dropped by the actor's own
-    /// volition.
+    /// The world position of the prop when it was released.
+    pub position: Vec3,
+    /// The linear velocity in m/s of the prop when it was released.
+    pub linear_velocity: Vec3,
+    /// The angular velocity in rad/s of the prop when it was released.
+    pub angular_velocity: Vec3,
+    /// The mass in kg of the prop, i.e. the one it has when not being held.
+    pub mass: Scalar,
+    /// The mass in kg the prop had while being held, see
+    /// [`AvianPickupActorHoldConfig::temporary_prop_mass`].
+    pub temporary_mass: Scalar,
+    /// How long the prop was held before being dropped.
+    pub hold_duration: Duration,
+    /// Whether the prop was forced to be dropped, e.g. by being too far away
+    /// from its target location. If `false`, the prop was dropped by the
+    /// actor's own volition. Same as [`PropDropReason::is_forced`].
     pub forced: bool,
+    /// Why the prop was dropped.
+    pub reason: PropDropReason,
+}
+
+/// The reason for a [`PropDropped`] event.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum PropDropReason {
+    /// The actor dropped the prop by its own volition.
+    Player,
+    /// The prop was too far away from its target location, see
+    /// [`AvianPickupActorHoldConfig::max_error`].
+    HoldErrorExceeded,
+    /// Holding the prop would have needed more force or torque than allowed by
+    /// [`HoldMode::PdController`].
+    ForceLimitExceeded,
+    /// Another actor took the prop away, see [`PropContestPolicy`].
+    Contested,
+    /// The prop got too far away from the actor, see
+    /// [`AvianPickupActorHoldConfig::break_distance`].
+    OutOfRange,
+    /// The actor placed the prop with
+    /// [`AvianPickupAction::Place`](crate::prelude::AvianPickupAction::Place).
+    Placed,
+    /// The prop was dropped from code, see
+    /// [`AvianPickupCommandsExt`](crate::prelude::AvianPickupCommandsExt).
+    Scripted,
+    /// The prop or the actor was despawned or is otherwise no longer valid,
+    /// e.g. because the [`AvianPickupActor`] was removed from the actor.
+    /// When the prop was despawned, observers on the prop don't fire, as it
+    /// is gone by the time the [`PropDropped`] event is triggered. Observe the
+    /// actor or read the [`PropDropped`] events instead.
+    Lost,
+}
+
+impl PropDropReason {
+    /// Returns `true` if the prop was not dropped by the actor's own volition.
+    pub fn is_forced(self) -> bool {
+        !matches!(self, Self::Player | Self::Placed)
+    }
+}
+
+/// Event sent when an actor starts pulling a prop towards itself.
+/// This is meant for the user to listen to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+///
+/// Also triggered as an observer event on both the prop and the actor.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PropPullStarted {
+    /// The prop being pulled.
+    pub prop: Entity,
+    /// The actor pulling the prop.
+    pub actor: Entity,
+}
+
+/// Event sent every time an actor applies a pull impulse to a prop.
+/// This is meant for the user to listen to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+///
+/// Also triggered as an observer event on both the prop and the actor.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PropPulled {
+    /// The prop being pulled.
+    pub prop: Entity,
+    /// The actor pulling the prop.
+    pub actor: Entity,
+    /// The impulse that was applied to the prop.
+    pub impulse: Vec3,
+}
+
+/// Event sent when an actor stops pulling a prop without picking it up,
+/// e.g. because the pull input was released or another prop was targeted.
+/// This is meant for the user to listen to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+///
+/// Also triggered as an observer event on both the prop and the actor.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PropPullCancelled {
+    /// The prop that was being pulled.
+    pub prop: Entity,
+    /// The actor that was pulling the prop.
+    pub actor: Entity,
+}
+
+/// Event sent when a prop is picked up by an actor.
+/// This is meant for the user to listen to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+///
+/// Also triggered as an observer event on both the prop and the actor.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PropPickedUp {
+    /// The picked up prop.
+    pub prop: Entity,
+    /// The actor that picked up the prop.
+    pub actor: Entity,
+}
+
+/// Event sent when an actor was about to hold a prop but could not,
+/// e.g. because another actor grabbed it first.
+/// This is meant for the user to listen to in order to play sound effects, etc.
+/// Sending this has no effect on the prop itself.
+///
+/// Also triggered as an observer event on both the prop and the actor.
+/// If the prop was despawned, i.e. the reason is
+/// [`HoldLostReason::PropDespawned`], it is only triggered on the actor, so
+/// observers on the prop don't fire.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PropHoldLost {
+    /// The prop that was lost.
+    pub prop: Entity,
+    /// The actor that lost the prop.
+    pub actor: Entity,
+    /// Why the prop was lost.
+    pub reason: HoldLostReason,
+}
+
+/// The reason for a [`PropHoldLost`] event.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Reflect)]
+#[reflect(Debug, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum HoldLostReason {
+    /// Another actor is already holding the prop.
+    HeldByOtherActor,
+    /// The prop was despawned or is no longer a valid rigid body.
+    PropDespawned,
 }
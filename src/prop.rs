@@ -1,28 +1,35 @@
 //! Components that can be placed on props to customize their behavior when
 //! picked up or thrown. All of these are optional.
-use std::ops::RangeInclusive;
+use std::{ops::RangeInclusive, time::Duration};
 
-use avian3d::math::Scalar;
+use avian3d::{math::Scalar, prelude::RigidBody};
 use bevy::prelude::*;
 
-use crate::prelude::AvianPickupActor;
+use crate::prelude::{
+    AvianPickupActor, AvianPickupActorCooldownConfig, NonDynamicPropPolicy, SpinAxis,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<(
         PreferredPickupRotation,
+        PickupGrabPoint,
         PitchRangeOverride,
         PreferredPickupDistanceOverride,
         PickupMassOverride,
         HeldProp,
         ThrownLinearSpeedOverride,
         ThrownAngularSpeedOverride,
+        ThrownSpin,
+        CooldownOverride,
+        NonDynamicPropPolicyOverride,
     )>();
 }
 
 pub(super) mod prelude {
     pub use super::{
-        HeldProp, PickupMassOverride, PitchRangeOverride, PreferredPickupDistanceOverride,
-        PreferredPickupRotation, ThrownAngularSpeedOverride, ThrownLinearSpeedOverride,
+        CooldownOverride, HeldProp, NonDynamicPropPolicyOverride, PickupGrabPoint,
+        PickupMassOverride, PitchRangeOverride, PreferredPickupDistanceOverride,
+        PreferredPickupRotation, ThrownAngularSpeedOverride, ThrownLinearSpeedOverride, ThrownSpin,
     };
 }
 
@@ -46,6 +53,21 @@ pub struct PreferredPickupRotation(pub Quat);
 #[derive(Debug, Clone, PartialEq, Component)]
 pub(crate) struct PrePickupRotation(pub Quat);
 
+/// Insert this on a prop to hold it at the given point in its local space
+/// instead of at its origin. The prop also rotates around this point.\
+/// Useful for e.g. holding a plank at one end or a sword by its handle.
+///
+/// Takes precedence over
+/// [`AvianPickupActorHoldConfig::grab_at_hit_point`](crate::prelude::AvianPickupActorHoldConfig::grab_at_hit_point).
+#[derive(Debug, Clone, Copy, PartialEq, Component, Default, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PickupGrabPoint(pub Vec3);
+
 /// Insert this on a prop to override
 /// [`AvianPickupActorHoldConfig::pitch_range`](crate::prelude::AvianPickupActorHoldConfig::pitch_range).
 #[derive(Debug, Clone, PartialEq, Component, Reflect)]
@@ -130,18 +152,100 @@ impl Default for ThrownAngularSpeedOverride {
     }
 }
 
+/// Insert this on a prop to override
+/// [`AvianPickupActorThrowConfig::spin_axis`](crate::prelude::AvianPickupActorThrowConfig::spin_axis)
+/// and
+/// [`AvianPickupActorThrowConfig::angular_speed_range`](crate::prelude::AvianPickupActorThrowConfig::angular_speed_range).\
+/// Useful for props that need controlled spin, e.g. frisbees, balls or knives.
+/// A [`ThrownAngularSpeedOverride`] on the same prop takes precedence over
+/// [`ThrownSpin::angular_speed_range`].
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ThrownSpin {
+    /// The axis the prop spins around.
+    pub axis: SpinAxis,
+    /// The range of angular speeds in rad/s that the prop can be thrown with.
+    pub angular_speed_range: RangeInclusive<Scalar>,
+}
+
+impl Default for ThrownSpin {
+    fn default() -> Self {
+        let config = AvianPickupActor::default().throw;
+        Self {
+            axis: config.spin_axis,
+            angular_speed_range: config.angular_speed_range,
+        }
+    }
+}
+
+/// Insert this on a prop to override
+/// [`AvianPickupActor::cooldown`](crate::prelude::AvianPickupActor::cooldown)
+/// for actions involving this prop.
+#[derive(Debug, Clone, PartialEq, Component, Default, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct CooldownOverride(pub AvianPickupActorCooldownConfig);
+
+/// Insert this on a prop to override
+/// [`AvianPickupActorPullConfig::non_dynamic_props`](crate::prelude::AvianPickupActorPullConfig::non_dynamic_props).
+/// Useful for e.g. letting players grab a specific kinematic lever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Default, Reflect)]
+#[reflect(Debug, Component, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct NonDynamicPropPolicyOverride(pub NonDynamicPropPolicy);
+
+/// The cached rigid body type that a non-dynamic object had before it was
+/// picked up and made dynamic.
+/// It will be restored again when it is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub(crate) struct NonPickupRigidBody(pub RigidBody);
+
 /// The cached mass that an object had before it was picked up
 /// that will be restored again when it is dropped.
 /// In other words, this is the mass before and after the pickup.
 #[derive(Debug, Clone, Copy, PartialEq, Component)]
 pub(crate) struct NonPickupMass(pub Scalar);
 
-/// Marker component for props that are held by an [`AvianPickupActor`].
-#[derive(Debug, Clone, Copy, PartialEq, Component, Hash, Default, Reflect)]
+/// Component for props that are held by an [`AvianPickupActor`].
+/// Inserted and removed automatically when a prop is picked up and dropped.
+///
+/// If the prop is despawned or this component is removed while the prop is
+/// held, the actor drops it with
+/// [`PropDropReason::Lost`](crate::prelude::PropDropReason::Lost).
+/// If the actor is despawned instead, the prop is released as if it was
+/// dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Hash, Reflect)]
 #[reflect(Debug, Component, Default, Hash, PartialEq)]
 #[cfg_attr(
     feature = "serialize",
     derive(serde::Serialize, serde::Deserialize),
     reflect(Serialize, Deserialize)
 )]
-pub struct HeldProp;
+pub struct HeldProp {
+    /// The actor holding the prop.
+    pub actor: Entity,
+    /// How long the prop has been held for.
+    pub duration: Duration,
+}
+
+impl Default for HeldProp {
+    fn default() -> Self {
+        Self {
+            actor: Entity::PLACEHOLDER,
+            duration: Duration::ZERO,
+        }
+    }
+}
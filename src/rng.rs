@@ -1,12 +1,15 @@
 use bevy::prelude::*;
-use rand::RngCore;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<RngSource>();
+    app.register_type::<AvianPickupRng>()
+        .init_resource::<RngSource>();
 }
 
 /// A resource that provides a source of randomness.
 /// Will fall back to [`rand::thread_rng()`] if no source is provided.
+/// Only used for actors without an [`AvianPickupRng`].
 #[derive(Resource, Default)]
 pub struct RngSource(pub Option<Box<dyn RngCore + Send + Sync>>);
 
@@ -28,6 +31,46 @@ impl RngCore for RngSource {
     }
 }
 
+/// A seeded source of randomness for a single
+/// [`AvianPickupActor`](crate::prelude::AvianPickupActor).
+/// Add this to the actor to make its throws independent of the system order
+/// and of other actors, e.g. for replays or lockstep networking.
+/// Actors without this component use the [`RngSource`] instead.
+///
+/// Uses [`ChaCha8Rng`], whose output is guaranteed to stay the same across
+/// platforms and versions.
+///
+/// # Example
+///
+/// ```
+/// # use avian_pickup::prelude::*;
+/// # use bevy::prelude::*;
+/// fn spawn_player(mut commands: Commands) {
+///     commands.spawn((
+///         Transform::default(),
+///         AvianPickupActor::default(),
+///         AvianPickupRng::from_seed(42),
+///     ));
+/// }
+/// ```
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(opaque)]
+#[reflect(Component, Debug)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupRng(pub ChaCha8Rng);
+
+impl AvianPickupRng {
+    /// Creates a new RNG from the given seed.
+    /// The same seed always produces the same sequence of throws.
+    pub fn from_seed(seed: u64) -> Self {
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
 impl RngSource {
     fn run<Out>(&mut self, f: impl FnOnce(&mut dyn RngCore) -> Out) -> Out {
         if let Some(rng) = self.0.as_mut() {
@@ -0,0 +1,273 @@
+//! Module for letting a single entity hold multiple props at once.
+
+use avian3d::prelude::*;
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{prelude::AvianPickupActorState, AvianPickupSystem};
+
+pub(super) mod prelude {
+    pub use super::AvianPickupSlots;
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AvianPickupSlots>().add_systems(
+        PhysicsSchedule,
+        sync_slot_owners
+            .in_set(PhysicsStepSet::First)
+            .before(AvianPickupSystem::First),
+    );
+}
+
+/// Component for an entity that can hold multiple props at once.
+/// Every slot is its own entity with an
+/// [`AvianPickupActor`](crate::prelude::AvianPickupActor), which
+/// means that every slot has its own configuration, state, target offset and
+/// hold error. Usually, the slots are children of the entity owning this
+/// component, with their [`Transform`] being the offset at which the prop is
+/// held.
+///
+/// To address a specific slot, send an
+/// [`AvianPickupInput`](crate::prelude::AvianPickupInput) to the slot's entity.
+/// When sending it to the owner of this component instead, the action is
+/// routed to a slot automatically:
+/// - [`AvianPickupAction::Pull`](crate::prelude::AvianPickupAction::Pull) and
+///   [`AvianPickupPullTarget`](crate::prelude::AvianPickupPullTarget)
+///   go to the slot that is already pulling, or else to the first slot that is
+///   not holding anything.
+/// - All other actions go to the last slot that is holding a prop.
+///
+/// Props held by one slot are ignored when another slot of the same owner is
+/// looking for props to pull.
+///
+/// The owner is treated as the body shared by all slots, e.g. a player rigid
+/// body with one slot per hand. Its colliders, as selected by each slot's
+/// [`AvianPickupActor::actor_filter`](crate::prelude::AvianPickupActor::actor_filter),
+/// are ignored when a slot is looking for props and are used instead of the
+/// slot's own colliders to keep held props from penetrating the body.
+///
+/// # Example
+/// ```
+/// # use avian_pickup::prelude::*;
+/// # use bevy::prelude::*;
+///
+/// fn setup_juggler(mut commands: Commands) {
+///     let left = commands
+///         .spawn((
+///             Transform::from_xyz(-0.3, 0.0, 0.0),
+///             AvianPickupActor::default(),
+///         ))
+///         .id();
+///     let right = commands
+///         .spawn((
+///             Transform::from_xyz(0.3, 0.0, 0.0),
+///             AvianPickupActor::default(),
+///         ))
+///         .id();
+///     commands
+///         .spawn((
+///             Name::new("Player Camera"),
+///             Camera3d::default(),
+///             AvianPickupSlots(vec![left, right]),
+///         ))
+///         .add_children(&[left, right]);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Component, Default, Reflect)]
+#[reflect(Debug, Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AvianPickupSlots(pub Vec<Entity>);
+
+impl AvianPickupSlots {
+    /// Returns the slot entity at the given index, if any.
+    pub fn get(&self, index: usize) -> Option<Entity> {
+        self.0.get(index).copied()
+    }
+
+    /// Returns `true` if the given entity is one of the slots.
+    pub fn contains(&self, slot: Entity) -> bool {
+        self.0.contains(&slot)
+    }
+
+    /// Finds the owner of the given slot.
+    pub(crate) fn find_owner<'a>(
+        slot: Entity,
+        q_slot_owner: &Query<&SlotOwner>,
+        q_slots: &'a Query<&AvianPickupSlots>,
+    ) -> Option<(Entity, &'a AvianPickupSlots)> {
+        let owner = q_slot_owner.get(slot).ok()?.0;
+        let slots = q_slots.get(owner).ok()?;
+        // The slot might have been removed from the owner since.
+        slots.contains(slot).then_some((owner, slots))
+    }
+
+    /// Picks the slot that should handle a pull.
+    pub(crate) fn slot_for_pull(
+        &self,
+        state: impl Fn(Entity) -> Option<AvianPickupActorState>,
+    ) -> Option<Entity> {
+        self.0
+            .iter()
+            .copied()
+            .find(|&slot| state(slot).is_some_and(|s| s.is_pulling()))
+            .or_else(|| {
+                self.0
+                    .iter()
+                    .copied()
+                    .find(|&slot| state(slot).is_some_and(|s| s.is_idle()))
+            })
+    }
+
+    /// Picks the slot that should handle an action on a held prop.
+    pub(crate) fn slot_for_held_prop(
+        &self,
+        state: impl Fn(Entity) -> Option<AvianPickupActorState>,
+    ) -> Option<Entity> {
+        self.0
+            .iter()
+            .rev()
+            .copied()
+            .find(|&slot| state(slot).is_some_and(|s| s.is_holding()))
+    }
+
+    /// Picks the slot that is charging a throw, so that releasing the charge
+    /// throws the prop of the slot that started it.
+    pub(crate) fn slot_for_throw_charge(
+        &self,
+        is_charging: impl Fn(Entity) -> bool,
+    ) -> Option<Entity> {
+        self.0.iter().copied().find(|&slot| is_charging(slot))
+    }
+}
+
+/// Points from a slot back to the entity whose [`AvianPickupSlots`] contain
+/// it, so that the owner can be found without looking through all of them.
+/// Kept up to date by [`sync_slot_owners`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub(crate) struct SlotOwner(pub(crate) Entity);
+
+fn sync_slot_owners(
+    mut commands: Commands,
+    q_changed_slots: Query<(Entity, &AvianPickupSlots), Changed<AvianPickupSlots>>,
+    q_slots: Query<&AvianPickupSlots>,
+    q_slot_owner: Query<(Entity, &SlotOwner)>,
+    mut removed_slots: RemovedComponents<AvianPickupSlots>,
+) {
+    let touched_owners: HashSet<Entity> = q_changed_slots
+        .iter()
+        .map(|(owner, _slots)| owner)
+        .chain(removed_slots.read())
+        .collect();
+    if touched_owners.is_empty() {
+        return;
+    }
+    // Release the slots that are no longer listed by their owner.
+    for (slot, owner) in q_slot_owner.iter() {
+        if !touched_owners.contains(&owner.0) {
+            continue;
+        }
+        let still_listed = q_slots.get(owner.0).is_ok_and(|slots| slots.contains(slot));
+        if !still_listed {
+            commands.entity(slot).remove::<SlotOwner>();
+        }
+    }
+    for (owner, slots) in q_changed_slots.iter() {
+        for &slot in slots.0.iter() {
+            if let Some(mut slot) = commands.get_entity(slot) {
+                slot.try_insert(SlotOwner(owner));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::ecs::system::RunSystemOnce as _;
+
+    use super::*;
+
+    fn owner_of(world: &mut World, slot: Entity) -> Option<Entity> {
+        world
+            .run_system_once_with(
+                slot,
+                |In(slot): In<Entity>,
+                 q_slot_owner: Query<&SlotOwner>,
+                 q_slots: Query<&AvianPickupSlots>| {
+                    AvianPickupSlots::find_owner(slot, &q_slot_owner, &q_slots)
+                        .map(|(owner, _slots)| owner)
+                },
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn slots_know_their_owner() {
+        let mut world = World::new();
+        let left = world.spawn_empty().id();
+        let right = world.spawn_empty().id();
+        let stranger = world.spawn_empty().id();
+        let owner = world.spawn(AvianPickupSlots(vec![left, right])).id();
+        world.run_system_once(sync_slot_owners).unwrap();
+        assert_eq!(owner_of(&mut world, left), Some(owner));
+        assert_eq!(owner_of(&mut world, right), Some(owner));
+        assert_eq!(owner_of(&mut world, stranger), None);
+
+        world
+            .get_mut::<AvianPickupSlots>(owner)
+            .unwrap()
+            .0
+            .retain(|&slot| slot != right);
+        world.run_system_once(sync_slot_owners).unwrap();
+        assert_eq!(owner_of(&mut world, left), Some(owner));
+        assert_eq!(owner_of(&mut world, right), None);
+        assert!(!world.entity(right).contains::<SlotOwner>());
+
+        world.entity_mut(owner).remove::<AvianPickupSlots>();
+        world.run_system_once(sync_slot_owners).unwrap();
+        assert!(!world.entity(left).contains::<SlotOwner>());
+    }
+
+    #[test]
+    fn actions_are_routed_to_slots_by_state() {
+        use AvianPickupActorState::*;
+
+        let left = Entity::from_raw(1);
+        let right = Entity::from_raw(2);
+        let slots = AvianPickupSlots(vec![left, right]);
+        let prop = Entity::from_raw(3);
+
+        let state = |left_state, right_state| {
+            move |slot: Entity| {
+                Some(if slot == left {
+                    left_state
+                } else {
+                    right_state
+                })
+            }
+        };
+        assert_eq!(slots.slot_for_pull(state(Idle, Idle)), Some(left));
+        assert_eq!(slots.slot_for_pull(state(Holding(prop), Idle)), Some(right));
+        assert_eq!(slots.slot_for_pull(state(Idle, Pulling(prop))), Some(right));
+        assert_eq!(
+            slots.slot_for_pull(state(Holding(prop), Holding(prop))),
+            None
+        );
+
+        assert_eq!(slots.slot_for_held_prop(state(Idle, Idle)), None);
+        assert_eq!(
+            slots.slot_for_held_prop(state(Holding(prop), Idle)),
+            Some(left)
+        );
+        assert_eq!(
+            slots.slot_for_held_prop(state(Holding(prop), Holding(prop))),
+            Some(right)
+        );
+
+        // Even though both slots are holding, only the left one is charging.
+        assert_eq!(slots.slot_for_throw_charge(|slot| slot == left), Some(left));
+        assert_eq!(slots.slot_for_throw_charge(|_| false), None);
+    }
+}
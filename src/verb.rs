@@ -1,8 +1,14 @@
+use avian3d::{math::Scalar, prelude::RigidBody};
 use bevy::{
     ecs::system::{EntityCommand, RunSystemOnce},
     prelude::*,
 };
 
+use crate::prelude::{
+    AvianPickupActorState, HeldProp, HoldLostReason, NonPickupRigidBody, PlacementPreview,
+    PropDropReason, PropHoldLost, ThrowTarget,
+};
+
 pub(super) fn plugin(_app: &mut App) {}
 
 /// This marks a state transition coming from either
@@ -14,41 +20,68 @@ pub(super) fn plugin(_app: &mut App) {}
 /// is.
 ///
 /// This type itself is just an usher for the actual marker components.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) enum Verb {
     /// Insert [`Throwing`] component and clear others
-    Throw(Entity),
+    Throw {
+        /// The prop to throw
+        prop: Entity,
+        /// Where to throw the prop
+        aim: ThrowAim,
+        /// The charge fraction of a charged throw
+        charge: Option<Scalar>,
+    },
     /// Insert [`Dropping`] component and clear others
     Drop {
         /// The prop to drop
         prop: Entity,
-        /// Whether the drop was forced to be dropped by
-        /// being too far away from its target location.
-        forced: bool,
+        /// Why the prop is dropped
+        reason: PropDropReason,
     },
-    /// Insert [`Pulling`] component and clear others
-    Pull,
+    /// Insert [`Pulling`] component and clear others.
+    /// Contains the specific prop to pull, if any.
+    Pull(Option<Entity>),
     /// Insert [`Holding`] component and clear others
     Hold(Entity),
 }
 
 #[derive(Debug, Clone, Copy, Component)]
-pub(crate) struct Throwing(pub(crate) Entity);
+pub(crate) struct Throwing {
+    pub(crate) prop: Entity,
+    pub(crate) aim: ThrowAim,
+    pub(crate) charge: Option<Scalar>,
+}
+
+/// How the linear velocity of a thrown prop is determined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ThrowAim {
+    /// Along the actor's forward direction with a speed calculated from the
+    /// actor's config.
+    Forward,
+    /// With exactly this velocity.
+    Velocity(Vec3),
+    /// On a ballistic arc towards the target.
+    Target(ThrowTarget),
+}
 
 #[derive(Debug, Clone, Copy, Component)]
 pub(crate) struct Dropping {
     pub(crate) prop: Entity,
-    pub(crate) forced: bool,
+    pub(crate) reason: PropDropReason,
+    /// Where to put the prop when it is placed. Taken from the
+    /// [`PlacementPreview`] when dropping, as that is removed together with
+    /// [`Holding`].
+    pub(crate) placement: Option<Transform>,
 }
 
 #[derive(Debug, Clone, Copy, Component)]
-pub(crate) struct Pulling;
+pub(crate) struct Pulling(pub(crate) Option<Entity>);
 
 #[derive(Debug, Clone, Copy, Component)]
 pub(crate) struct Holding(pub(crate) Entity);
 
 /// Sets or clears the [`Verb`] of an actor.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) struct SetVerb(pub(crate) Option<Verb>);
 
 impl SetVerb {
@@ -59,24 +92,60 @@ impl SetVerb {
 
 impl EntityCommand for SetVerb {
     fn apply(self, actor: Entity, world: &mut World) {
-        world.run_system_once_with((actor, self.0), set_verb);
+        if let Err(err) = world.run_system_once_with((actor, self.0), set_verb) {
+            error!("Failed to set verb of actor: {err}");
+        }
     }
 }
 
 fn set_verb(
     In((actor, verb)): In<(Entity, Option<Verb>)>,
     mut commands: Commands,
-    q_actor: Query<(Has<Throwing>, Has<Dropping>, Has<Pulling>, Has<Holding>)>,
+    mut q_actor: Query<(
+        Has<Throwing>,
+        Option<&Dropping>,
+        Option<&Pulling>,
+        Has<Holding>,
+        &mut AvianPickupActorState,
+        Option<&PlacementPreview>,
+    )>,
+    q_held_prop: Query<&HeldProp>,
+    q_holding: Query<&Holding>,
+    q_rigid_body: Query<(&RigidBody, Option<&NonPickupRigidBody>)>,
+    mut w_hold_lost: EventWriter<PropHoldLost>,
 ) {
-    let Ok((throwing, dropping, pulling, holding)) = q_actor.get(actor) else {
+    let Ok((throwing, dropping, pulling, holding, mut state, placement)) = q_actor.get_mut(actor)
+    else {
         error!("Actor entity was deleted or in an invalid state. Ignoring.");
         return;
     };
+    // Props that are only dynamic while being held get their original rigid
+    // body back as soon as they are released, so a throw would have no effect
+    // on them. Drop them instead.
+    let verb = match verb {
+        Some(Verb::Throw { prop, .. })
+            if q_rigid_body
+                .get(prop)
+                .is_ok_and(|(rigid_body, non_pickup)| {
+                    !non_pickup.map_or(*rigid_body, |n| n.0).is_dynamic()
+                }) =>
+        {
+            Some(Verb::Drop {
+                prop,
+                reason: PropDropReason::Player,
+            })
+        }
+        verb => verb,
+    };
+    let dropped_prop = dropping.map(|dropping| dropping.prop);
+    let dropping = dropping.is_some();
+    let pulled_target = pulling.map(|pulling| pulling.0);
+    let pulling = pulling.is_some();
     let mut commands = commands.entity(actor);
     match verb {
-        Some(Verb::Throw(prop)) => {
+        Some(Verb::Throw { prop, aim, charge }) => {
             if !throwing {
-                commands.insert(Throwing(prop));
+                commands.insert(Throwing { prop, aim, charge });
             }
             if dropping {
                 commands.remove::<Dropping>();
@@ -88,9 +157,16 @@ fn set_verb(
                 commands.remove::<Holding>();
             }
         }
-        Some(Verb::Drop { prop, forced }) => {
+        Some(Verb::Drop { prop, reason }) => {
             if !dropping {
-                commands.insert(Dropping { prop, forced });
+                let placement = placement
+                    .filter(|placement| reason == PropDropReason::Placed && placement.is_valid())
+                    .map(PlacementPreview::transform);
+                commands.insert(Dropping {
+                    prop,
+                    reason,
+                    placement,
+                });
             }
             if throwing {
                 commands.remove::<Throwing>();
@@ -102,9 +178,9 @@ fn set_verb(
                 commands.remove::<Holding>();
             }
         }
-        Some(Verb::Pull) => {
-            if !pulling {
-                commands.insert(Pulling);
+        Some(Verb::Pull(target)) => {
+            if pulled_target != Some(target) {
+                commands.insert(Pulling(target));
             }
             if throwing {
                 commands.remove::<Throwing>();
@@ -117,13 +193,33 @@ fn set_verb(
             }
         }
         Some(Verb::Hold(prop)) => {
+            let held_by_other_actor = q_held_prop.get(prop).is_ok_and(|held| {
+                held.actor != actor
+                    && q_holding
+                        .get(held.actor)
+                        .is_ok_and(|holding| holding.0 == prop)
+            });
+            if !holding && held_by_other_actor {
+                // Another actor picked up the prop earlier this update.
+                *state = AvianPickupActorState::Idle;
+                let event = PropHoldLost {
+                    actor,
+                    prop,
+                    reason: HoldLostReason::HeldByOtherActor,
+                };
+                w_hold_lost.send(event);
+                commands.commands().trigger_targets(event, [prop, actor]);
+                return;
+            }
             if !holding {
                 commands.insert(Holding(prop));
             }
             if throwing {
                 commands.remove::<Throwing>();
             }
-            if dropping {
+            // When switching from one prop to another, the previous prop
+            // still needs to be dropped properly.
+            if dropped_prop == Some(prop) {
                 commands.remove::<Dropping>();
             }
             if pulling {
@@ -141,3 +237,47 @@ fn set_verb(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Throws a held prop and returns whether the actor ends up throwing or
+    /// dropping it.
+    fn throw_prop(prop: impl Bundle) -> (bool, bool) {
+        let mut world = World::new();
+        world.init_resource::<Events<PropHoldLost>>();
+        let prop = world.spawn(prop).id();
+        let actor = world
+            .spawn((AvianPickupActorState::Holding(prop), Holding(prop)))
+            .id();
+        SetVerb::new(Verb::Throw {
+            prop,
+            aim: ThrowAim::Forward,
+            charge: None,
+        })
+        .apply(actor, &mut world);
+        let actor = world.entity(actor);
+        assert!(!actor.contains::<Holding>());
+        (
+            actor
+                .get::<Throwing>()
+                .is_some_and(|throwing| throwing.prop == prop),
+            actor.get::<Dropping>().is_some_and(|dropping| {
+                dropping.prop == prop && dropping.reason == PropDropReason::Player
+            }),
+        )
+    }
+
+    #[test]
+    fn dynamic_props_are_thrown() {
+        assert_eq!(throw_prop(RigidBody::Dynamic), (true, false));
+    }
+
+    #[test]
+    fn props_made_dynamic_are_dropped_instead_of_thrown() {
+        let prop = (RigidBody::Dynamic, NonPickupRigidBody(RigidBody::Static));
+        assert_eq!(throw_prop(prop), (false, true));
+        assert_eq!(throw_prop(RigidBody::Kinematic), (false, true));
+    }
+}